@@ -0,0 +1,81 @@
+//! Чтение YAML-файлов из zip и tar архивов без распаковки на диск.
+//!
+//! Отбираются только записи, которые конфигурация считает YAML-файлами;
+//! ошибка чтения отдельной записи сохраняется в ней и не прерывает обход.
+
+use crate::config::Config;
+use anyhow::{bail, Context};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// YAML-файл, прочитанный из архива без распаковки на диск
+pub struct ArchiveEntry {
+    pub name: String,
+    pub content: anyhow::Result<String>,
+}
+
+pub fn is_archive<P: AsRef<Path>>(path: P) -> bool {
+    let name = path.as_ref().to_string_lossy().to_lowercase();
+    [".zip", ".tar", ".tar.gz", ".tgz"].iter().any(|ext| name.ends_with(ext))
+}
+
+pub fn read_yaml_entries<P: AsRef<Path>>(path: P, config: &Config) -> anyhow::Result<Vec<ArchiveEntry>> {
+    let path = path.as_ref();
+    let name = path.to_string_lossy().to_lowercase();
+    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+
+    if name.ends_with(".zip") {
+        read_zip(file, config)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        read_tar(GzDecoder::new(file), config)
+    } else if name.ends_with(".tar") {
+        read_tar(file, config)
+    } else {
+        bail!("Unsupported archive format: {}", path.display())
+    }
+}
+
+fn read_zip(file: File, config: &Config) -> anyhow::Result<Vec<ArchiveEntry>> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut entries = vec![];
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+
+        if !entry.is_file() || !config.is_yaml_file(entry.name()) {
+            continue;
+        }
+
+        let mut content = String::new();
+        entries.push(ArchiveEntry {
+            name: entry.name().to_string(),
+            content: entry.read_to_string(&mut content).map(|_| content).map_err(Into::into),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn read_tar<R: Read>(reader: R, config: &Config) -> anyhow::Result<Vec<ArchiveEntry>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = vec![];
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+
+        if !entry.header().entry_type().is_file() || !config.is_yaml_file(&name) {
+            continue;
+        }
+
+        let mut content = String::new();
+        entries.push(ArchiveEntry {
+            name,
+            content: entry.read_to_string(&mut content).map(|_| content).map_err(Into::into),
+        });
+    }
+
+    Ok(entries)
+}
@@ -1,3 +1,6 @@
+use crate::config::ConfigFormat;
+use crate::exporter::OutputFormat;
+use crate::template::TemplateMode;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -7,11 +10,24 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
+    /// Файл конфигурации; при повторении файлы накладываются по порядку
     #[arg(short, long, global = true)]
-    pub config_path: Option<String>,
+    pub config_path: Vec<String>,
+
+    /// Не искать конфигурацию, использовать встроенные значения
+    #[arg(long, global = true, conflicts_with = "config_path")]
+    pub no_config: bool,
 
     #[arg(short, long, global = true)]
     pub verbose: bool,
+
+    /// Завершаться с ошибкой, если предупреждений больше N
+    #[arg(long, global = true, value_name = "N")]
+    pub max_warnings: Option<usize>,
+
+    /// Считать предупреждения ошибками при выборе кода выхода
+    #[arg(long, global = true)]
+    pub strict: bool,
 }
 
 #[derive(Subcommand)]
@@ -19,7 +35,13 @@ pub enum Commands {
     /// Проверить файл или директорию
     Check {
         /// Путь к файлу или директории
-        path: String,
+        #[arg(required_unless_present = "files_from")]
+        path: Option<String>,
+
+        /// Проверить файлы из списка (по пути на строке, `-` — стандартный ввод)
+        /// вместо обхода директории
+        #[arg(long, value_name = "PATH", conflicts_with = "path")]
+        files_from: Option<String>,
 
         /// Автоматически исправлять найденные проблемы
         #[arg(short, long)]
@@ -28,6 +50,40 @@ pub enum Commands {
         /// Выводить только ошибки
         #[arg(short, long)]
         quiet: bool,
+
+        /// Печатать только пути изменённых файлов, по одному на строку
+        /// (с `-O json` — JSON-массивом); отчёт пишется лишь в `--output-file`
+        #[arg(long, requires = "fix")]
+        print_fixed: bool,
+
+        /// Исправлять и файлы с синтаксическими ошибками
+        #[arg(long, requires = "fix")]
+        fix_broken: bool,
+
+        /// Формат вывода результатов
+        #[arg(short = 'O', long, value_enum, default_value_t = OutputFormat::Text)]
+        output_format: OutputFormat,
+
+        /// Записать результаты в файл вместо стандартного вывода
+        #[arg(long)]
+        output_file: Option<String>,
+
+        /// Печатать в stderr JSON с кодом выхода и числом находок
+        #[arg(long)]
+        exit_json: bool,
+
+        /// Показать исправления как unified diff, не изменяя файлы;
+        /// код выхода 1, если какой-либо файл изменился бы
+        #[arg(long, conflicts_with = "fix")]
+        diff: bool,
+
+        /// Проверять шаблоны, маскируя директивы шаблонизатора
+        #[arg(long, value_enum)]
+        template: Option<TemplateMode>,
+
+        /// После проверки следить за путём и перепроверять изменённые файлы
+        #[arg(long, conflicts_with_all = ["fix", "diff", "files_from"])]
+        watch: bool,
     },
 
     /// Валидация с использованием JSON Schema
@@ -48,6 +104,33 @@ pub enum Commands {
         /// Форматировать файлы на месте
         #[arg(short, long)]
         in_place: bool,
+
+        /// Число потоков форматирования (0 — по числу ядер)
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+
+        /// Печатать пути изменённых файлов, по одному на строку
+        #[arg(long, requires = "in_place")]
+        print_fixed: bool,
+
+        /// Показать изменения как unified diff; код выхода 1, если они есть
+        #[arg(long, conflicts_with = "in_place")]
+        diff: bool,
+    },
+
+    /// Объединить JSON-отчёты нескольких запусков в один
+    MergeReports {
+        /// Отчёты, сохранённые с `-O json`
+        #[arg(required = true)]
+        reports: Vec<String>,
+
+        /// Формат объединённого отчёта
+        #[arg(short = 'O', long, value_enum, default_value_t = OutputFormat::Json)]
+        output_format: OutputFormat,
+
+        /// Записать отчёт в файл вместо стандартного вывода
+        #[arg(long)]
+        output_file: Option<String>,
     },
 
     /// Управление конфигурацией
@@ -55,5 +138,28 @@ pub enum Commands {
         /// Сгенерировать конфигурационный файл
         #[arg(short, long)]
         generate: bool,
+
+        /// Путь для сгенерированного файла
+        #[arg(short, long, default_value = ".yamllint.yaml")]
+        output: String,
+
+        /// Перезаписать существующий файл
+        #[arg(short, long)]
+        force: bool,
+
+        /// Формат вывода действующей конфигурации
+        #[arg(short = 'O', long, value_enum, default_value_t = ConfigFormat::Text, conflicts_with = "generate")]
+        output_format: ConfigFormat,
     },
 }
+
+impl Commands {
+    /// Путь, от которого ищется конфигурация
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Commands::Check { path, .. } => path.as_deref(),
+            Commands::Validate { path, .. } | Commands::Format { path, .. } => Some(path),
+            Commands::MergeReports { .. } | Commands::Config { .. } => None,
+        }
+    }
+}
@@ -1,13 +1,120 @@
+use anyhow::Context;
+use crate::template::TemplateMode;
+use clap::ValueEnum;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Встроенный профиль с исключениями и расширениями по умолчанию
+const DEFAULT_PROFILE: &str = include_str!("profile.yaml");
+
+/// Встроенная конфигурация для `extends: relaxed`, поверх значений по умолчанию
+const RELAXED_PRESET: &str = include_str!("relaxed.yaml");
+
+/// Переменная окружения с путём к базовому профилю организации
+pub const PROFILE_ENV: &str = "YAMLLINT_PROFILE";
+
+lazy_static! {
+    // Профиль читается один раз: значения по умолчанию нужны при каждом разборе
+    // конфигурации, а предупреждение о неверном профиле достаточно вывести однажды
+    static ref PROFILE: (Vec<String>, Vec<String>) = Profile::load(env::var(PROFILE_ENV).ok().as_deref());
+}
+
+/// Имена файлов конфигурации, которые ищутся автоматически, в порядке приоритета
+pub const CONFIG_FILES: [&str; 3] = [".yamllint.yaml", ".yamllint.yml", ".yamllint"];
+
+/// Файл исключений рядом с проверяемыми файлами, синтаксис как у .gitignore
+pub const IGNORE_FILE: &str = ".yamllintignore";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
+    /// Базовая конфигурация: путь к файлу или встроенная `default` / `relaxed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
     pub rules: RuleConfig,
     pub format: FormatConfig,
+    #[serde(default = "default_exclude")]
     pub exclude: Vec<String>,
+    #[serde(default = "default_extensions")]
+    pub extensions: Vec<String>,
+    /// Версия YAML, правила неявного приведения которой проверяются;
+    /// директива `%YAML` в файле имеет приоритет
+    #[serde(default)]
+    pub yaml_version: YamlVersion,
+    #[serde(default)]
+    pub includes: IncludesConfig,
+    /// Имя правила -> шаблоны путей, к которым оно применяется;
+    /// правило без записи применяется ко всем файлам
+    #[serde(default)]
+    pub rule_scopes: HashMap<String, Vec<String>>,
+    /// Файлы — шаблоны, директивы которых маскируются перед проверкой
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<TemplateMode>,
+    /// Файл, из которого загружена конфигурация; `None` — встроенные значения
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+/// Формат вывода действующей конфигурации в `config`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ConfigFormat {
+    /// Отладочное представление для чтения человеком
+    Text,
+    Json,
+    /// Можно сохранить и передать через `--config-path`
+    Yaml,
+}
+
+/// Профиль задаёт значения по умолчанию, которые командам удобно
+/// распространять без перекомпиляции
+#[derive(Debug, Deserialize)]
+struct Profile {
+    exclude: Option<Vec<String>>,
+    extensions: Option<Vec<String>>,
+}
+
+impl Profile {
+    /// Встроенный профиль, поверх которого накладывается профиль по пути из `YAMLLINT_PROFILE`
+    fn load(path: Option<&str>) -> (Vec<String>, Vec<String>) {
+        let bundled: Profile = serde_yaml::from_str(DEFAULT_PROFILE).expect("bundled profile is valid YAML");
+        let mut exclude = bundled.exclude.unwrap_or_default();
+        let mut extensions = bundled.extensions.unwrap_or_default();
+
+        if let Some(path) = path {
+            let custom = fs::read_to_string(path)
+                .with_context(|| format!("failed to read profile at {}", path))
+                .and_then(|content| {
+                    serde_yaml::from_str::<Profile>(&content)
+                        .with_context(|| format!("failed to parse profile at {}", path))
+                });
+
+            match custom {
+                Ok(custom) => {
+                    exclude = custom.exclude.unwrap_or(exclude);
+                    extensions = custom.extensions.unwrap_or(extensions);
+                }
+                Err(e) => eprintln!("Warning: {:#}", e),
+            }
+        }
+
+        (exclude, extensions)
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_exclude() -> Vec<String> {
+    PROFILE.0.clone()
+}
+
+fn default_extensions() -> Vec<String> {
+    PROFILE.1.clone()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -20,18 +127,107 @@ pub struct RuleConfig {
     pub value_types: ValueTypesRule,
     pub duplicates: SeverityRule,
     pub quotes: QuotesRule,
+    #[serde(default = "SeverityRule::warning")]
+    pub implicit_dates: SeverityRule,
+    #[serde(default)]
+    pub port_range: PortRangeRule,
+    #[serde(default = "SeverityRule::warning")]
+    pub indent_sequence: SeverityRule,
+    #[serde(default = "SeverityRule::off")]
+    pub final_line: SeverityRule,
+    #[serde(default)]
+    pub block_scalar_style: BlockScalarStyleRule,
+    #[serde(default)]
+    pub environment_markers: EnvironmentMarkersRule,
+    #[serde(default = "SeverityRule::warning")]
+    pub stray_comma: SeverityRule,
+    #[serde(default)]
+    pub no_inline_secrets: InlineSecretsRule,
+    #[serde(default)]
+    pub homogeneous_values: HomogeneousValuesRule,
+    #[serde(default)]
+    pub comments_indentation: CommentsIndentationRule,
+    #[serde(default)]
+    pub comments: CommentsRule,
+    #[serde(default)]
+    pub duplicate_blocks: DuplicateBlocksRule,
+    #[serde(default)]
+    pub document_start: DocumentStartRule,
+    #[serde(default)]
+    pub key_ordering: KeyOrderingRule,
+    #[serde(default)]
+    pub truthy: TruthyRule,
+    #[serde(default)]
+    pub key_occurrence_limit: KeyOccurrenceLimitRule,
+    #[serde(default = "SeverityRule::warning")]
+    pub leading_zeros: SeverityRule,
+    #[serde(default)]
+    pub block_scalar_chomping: BlockScalarChompingRule,
+    #[serde(default)]
+    pub float_precision: FloatPrecisionRule,
+    #[serde(default = "SeverityRule::off")]
+    pub strict_indent: SeverityRule,
+    #[serde(default)]
+    pub max_depth: MaxDepthRule,
+    #[serde(default = "SeverityRule::off")]
+    pub document_start_consistency: SeverityRule,
+    #[serde(default)]
+    pub compare_to_defaults: CompareToDefaultsRule,
+    #[serde(default)]
+    pub duplicate_named_items: DuplicateNamedItemsRule,
+    #[serde(default)]
+    pub key_order_template: KeyOrderTemplateRule,
+    #[serde(default = "SeverityRule::warning")]
+    pub control_chars: SeverityRule,
+    #[serde(default)]
+    pub forbidden_keys: ForbiddenKeysRule,
+    #[serde(default)]
+    pub quoted_strings: QuotedStringsRule,
+    #[serde(default)]
+    pub colons: ColonsRule,
+    #[serde(default)]
+    pub hyphens: HyphensRule,
+    #[serde(default)]
+    pub brackets: BracketsRule,
+    #[serde(default)]
+    pub braces: BracesRule,
+    #[serde(default)]
+    pub commas: CommasRule,
+    #[serde(default)]
+    pub new_line_at_end_of_file: NewlinesRule,
+    #[serde(default)]
+    pub line_endings: LineEndingsRule,
+    #[serde(default)]
+    pub key_naming: KeyNamingRule,
+    #[serde(default)]
+    pub anchors: AnchorsRule,
+    #[serde(default)]
+    pub octal_values: OctalValuesRule,
+    #[serde(default)]
+    pub float_values: FloatValuesRule,
+    #[serde(default)]
+    pub empty_values: EmptyValuesRule,
+    #[serde(default)]
+    pub merge_keys: MergeKeysRule,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct IndentationRule {
     pub spaces: usize,
     pub check_multi_line_strings: bool,
+    /// Табуляция в отступе недопустима в YAML
+    #[serde(default = "default_true")]
+    pub forbid_tabs: bool,
+    #[serde(default = "Severity::error")]
+    pub level: Severity,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LineLengthRule {
     pub max: usize,
     pub allow_non_breakable_words: bool,
+    #[serde(default = "Severity::warning")]
+    pub level: Severity,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -39,17 +235,40 @@ pub struct EmptyLinesRule {
     pub max_start: usize,
     pub max_end: usize,
     pub max_consecutive: usize,
+    #[serde(default = "Severity::warning")]
+    pub level: Severity,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RequiredFieldsRule {
     pub paths: HashMap<String, Vec<String>>,
+    /// Обязательные поля для документов, выбранных по содержимому
+    #[serde(default)]
+    pub content: Vec<ContentRequirement>,
+    #[serde(default = "Severity::error")]
+    pub level: Severity,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentRequirement {
+    pub when: ContentSelector,
+    pub fields: Vec<String>,
+}
+
+/// Документ выбирается, если значение по пути (`/kind`, `/metadata/labels/app`)
+/// равно `equals`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentSelector {
+    pub path: String,
+    pub equals: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ValueTypesRule {
     pub strict_numbers: bool,
     pub check_bool_values: bool,
+    #[serde(default = "Severity::warning")]
+    pub level: Severity,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -57,6 +276,699 @@ pub struct QuotesRule {
     pub prefer_double: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PortRangeRule {
+    pub keys: Vec<String>,
+    pub level: Severity,
+}
+
+impl Default for PortRangeRule {
+    fn default() -> Self {
+        PortRangeRule {
+            keys: vec!["port".to_string(), "targetPort".to_string(), "containerPort".to_string()],
+            level: Severity::Error,
+        }
+    }
+}
+
+/// Файлы, подключённые тегами вроде `!include other.yaml`, проверяются
+/// вместе с подключающим; путь берётся относительно него
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IncludesConfig {
+    pub enabled: bool,
+    pub tags: Vec<String>,
+}
+
+impl Default for IncludesConfig {
+    fn default() -> Self {
+        IncludesConfig {
+            enabled: false,
+            tags: vec!["!include".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Default)]
+pub enum YamlVersion {
+    /// `yes`/`no`/`on`/`off` — булевы значения, даты — timestamp
+    #[default]
+    #[serde(rename = "1.1")]
+    V1_1,
+    /// Булевы только `true`/`false`, неявных дат нет
+    #[serde(rename = "1.2")]
+    V1_2,
+}
+
+impl YamlVersion {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text {
+            "1.1" => Some(YamlVersion::V1_1),
+            "1.2" => Some(YamlVersion::V1_2),
+            _ => None,
+        }
+    }
+}
+
+// Без кавычек `yaml_version: 1.2` читается как число, поэтому принимаем и его
+impl<'de> Deserialize<'de> for YamlVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = match serde_yaml::Value::deserialize(deserializer)? {
+            serde_yaml::Value::Number(n) => n.to_string(),
+            serde_yaml::Value::String(s) => s,
+            other => return Err(serde::de::Error::custom(format!("invalid yaml_version: {:?}", other))),
+        };
+
+        YamlVersion::parse(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("unsupported yaml_version '{}', expected 1.1 or 1.2", text)))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockScalarStyleRule {
+    pub prefer: BlockScalarStyle,
+    pub level: Severity,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum BlockScalarStyle {
+    /// `|`
+    #[serde(rename = "literal")]
+    Literal,
+    /// `>`
+    #[serde(rename = "folded")]
+    Folded,
+    /// Стиль, преобладающий в файле
+    #[serde(rename = "consistent")]
+    Consistent,
+}
+
+impl Default for BlockScalarStyleRule {
+    fn default() -> Self {
+        BlockScalarStyleRule {
+            prefer: BlockScalarStyle::Consistent,
+            level: Severity::Warning,
+        }
+    }
+}
+
+/// Для файлов, подходящих под glob, требует заданные значения ключей,
+/// например `*.prod.yaml` -> `environment: production`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EnvironmentMarkersRule {
+    pub files: HashMap<String, HashMap<String, String>>,
+    pub level: Severity,
+}
+
+impl Default for EnvironmentMarkersRule {
+    fn default() -> Self {
+        EnvironmentMarkersRule {
+            files: HashMap::new(),
+            level: Severity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InlineSecretsRule {
+    /// Подстроки имён ключей (без учёта регистра), значения которых считаются секретами
+    pub keys: Vec<String>,
+    pub level: Severity,
+}
+
+impl Default for InlineSecretsRule {
+    fn default() -> Self {
+        InlineSecretsRule {
+            keys: vec![
+                "password".to_string(),
+                "token".to_string(),
+                "secret".to_string(),
+                "apiKey".to_string(),
+            ],
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HomogeneousValuesRule {
+    /// Допустимая доля значений меньшинства (скаляров или коллекций) в маппинге
+    pub max_mixed_ratio: f64,
+    pub level: Severity,
+}
+
+impl Default for HomogeneousValuesRule {
+    fn default() -> Self {
+        HomogeneousValuesRule {
+            max_mixed_ratio: 0.25,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommentsIndentationRule {
+    /// Комментарий должен совпадать по отступу только со следующей строкой;
+    /// иначе допускается и отступ предыдущей (комментарий, закрывающий блок)
+    pub strict: bool,
+    pub level: Severity,
+}
+
+impl Default for CommentsIndentationRule {
+    fn default() -> Self {
+        CommentsIndentationRule {
+            strict: false,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommentsRule {
+    /// После `#` должен идти пробел (`# text`, а не `#text`)
+    pub require_starting_space: bool,
+    /// Минимум пробелов между содержимым строки и комментарием в её конце
+    pub min_spaces_from_content: usize,
+    pub level: Severity,
+}
+
+impl Default for CommentsRule {
+    fn default() -> Self {
+        CommentsRule {
+            require_starting_space: true,
+            min_spaces_from_content: 2,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateBlocksRule {
+    /// Минимальный размер блока в узлах (сам блок, ключи и значения)
+    pub min_size: usize,
+    pub level: Severity,
+}
+
+impl Default for DuplicateBlocksRule {
+    fn default() -> Self {
+        DuplicateBlocksRule {
+            min_size: 6,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentStartRule {
+    /// `true` — маркер `---` в начале файла обязателен, `false` — запрещён
+    pub present: bool,
+    pub level: Severity,
+}
+
+impl Default for DocumentStartRule {
+    fn default() -> Self {
+        DocumentStartRule {
+            present: true,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyOrderingRule {
+    /// Сравнивать ключи без учёта регистра
+    pub ignore_case: bool,
+    pub level: Severity,
+}
+
+impl Default for KeyOrderingRule {
+    fn default() -> Self {
+        KeyOrderingRule {
+            ignore_case: false,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TruthyRule {
+    /// Допустимые написания из набора YAML 1.1 (`yes`, `on`, `Y`, ...)
+    pub allowed_values: Vec<String>,
+    /// Проверять и ключи маппингов (`on:` в GitHub Actions)
+    pub check_keys: bool,
+    pub level: Severity,
+}
+
+impl Default for TruthyRule {
+    fn default() -> Self {
+        TruthyRule {
+            allowed_values: vec!["true".to_string(), "false".to_string()],
+            check_keys: true,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyOccurrenceLimitRule {
+    /// Шаблон пути ключа -> максимальное число вхождений в документе.
+    /// Путь записывается через точку без индексов последовательностей:
+    /// `*` — один ключ, `**` — любая глубина (`spec.**.image`)
+    pub limits: HashMap<String, usize>,
+    pub level: Severity,
+}
+
+impl Default for KeyOccurrenceLimitRule {
+    fn default() -> Self {
+        KeyOccurrenceLimitRule {
+            limits: HashMap::new(),
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaxDepthRule {
+    /// Допустимое число уровней вложенности: ключ верхнего уровня — 1
+    pub max: usize,
+    pub level: Severity,
+}
+
+impl Default for MaxDepthRule {
+    fn default() -> Self {
+        MaxDepthRule {
+            max: 6,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompareToDefaultsRule {
+    /// Эталонный файл; относительный путь считается от файла конфигурации
+    pub reference: Option<String>,
+    /// Сообщать о ключах эталона, которых нет в документе
+    pub report_missing: bool,
+    /// Сообщать о ключах документа, которых нет в эталоне
+    pub report_extra: bool,
+    pub level: Severity,
+}
+
+impl Default for CompareToDefaultsRule {
+    fn default() -> Self {
+        CompareToDefaultsRule {
+            reference: None,
+            report_missing: false,
+            report_extra: false,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateNamedItemsRule {
+    /// Шаблоны путей последовательностей, записанные как в `key_occurrence_limit.limits`
+    pub sequences: Vec<String>,
+    /// Поле элемента, которое должно быть уникальным в последовательности
+    pub field: String,
+    pub level: Severity,
+}
+
+impl Default for DuplicateNamedItemsRule {
+    fn default() -> Self {
+        DuplicateNamedItemsRule {
+            sequences: ["**.env", "**.ports", "**.volumes", "**.volumeMounts", "**.containers", "**.initContainers"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            field: "name".to_string(),
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyOrderTemplateRule {
+    /// Порядок ключей верхнего уровня; ключи не из списка не проверяются
+    pub order: Vec<String>,
+    pub level: Severity,
+}
+
+impl Default for KeyOrderTemplateRule {
+    fn default() -> Self {
+        KeyOrderTemplateRule {
+            order: ["apiVersion", "kind", "metadata", "spec"].iter().map(|s| s.to_string()).collect(),
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ForbiddenKeysRule {
+    /// Имена ключей без учёта регистра; допускаются шаблоны вроде `*_token`
+    pub keys: Vec<String>,
+    pub level: Severity,
+}
+
+impl Default for ForbiddenKeysRule {
+    fn default() -> Self {
+        ForbiddenKeysRule {
+            keys: vec![],
+            level: Severity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuotedStringsRule {
+    pub required: QuoteRequirement,
+    pub quote_type: QuoteType,
+    pub level: Severity,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuoteRequirement {
+    /// Строковые значения всегда в кавычках
+    Always,
+    /// Кавычки только там, где без них значение прочитается иначе
+    OnlyWhenNeeded,
+    /// Кавычки не требуются, проверяется только `quote_type`
+    Never,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteType {
+    Any,
+    Single,
+    Double,
+}
+
+impl Default for QuotedStringsRule {
+    fn default() -> Self {
+        QuotedStringsRule {
+            required: QuoteRequirement::Always,
+            quote_type: QuoteType::Any,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColonsRule {
+    /// Пробелов между ключом и `:`
+    pub max_spaces_before: usize,
+    /// Пробелов между `:` и значением на той же строке
+    pub max_spaces_after: usize,
+    pub level: Severity,
+}
+
+impl Default for ColonsRule {
+    fn default() -> Self {
+        ColonsRule {
+            max_spaces_before: 0,
+            max_spaces_after: 1,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HyphensRule {
+    /// Пробелов между `-` и содержимым элемента
+    pub max_spaces_after: usize,
+    pub level: Severity,
+}
+
+impl Default for HyphensRule {
+    fn default() -> Self {
+        HyphensRule {
+            max_spaces_after: 1,
+            level: Severity::Warning,
+        }
+    }
+}
+
+/// Пробелы сразу после `[` и перед `]` в flow-последовательностях
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BracketsRule {
+    pub min_spaces_inside: usize,
+    pub max_spaces_inside: usize,
+    pub level: Severity,
+}
+
+/// Те же настройки для `{` и `}` flow-маппингов
+pub type BracesRule = BracketsRule;
+
+impl Default for BracketsRule {
+    fn default() -> Self {
+        BracketsRule {
+            min_spaces_inside: 0,
+            max_spaces_inside: 0,
+            level: Severity::Warning,
+        }
+    }
+}
+
+/// Пробелы вокруг запятых в flow-коллекциях
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommasRule {
+    pub max_spaces_before: usize,
+    pub min_spaces_after: usize,
+    pub max_spaces_after: usize,
+    pub level: Severity,
+}
+
+impl Default for CommasRule {
+    fn default() -> Self {
+        CommasRule {
+            max_spaces_before: 0,
+            min_spaces_after: 1,
+            max_spaces_after: 1,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewlinesRule {
+    /// Файл должен заканчиваться ровно одним переводом строки
+    pub require_final_newline: bool,
+    pub level: Severity,
+}
+
+impl Default for NewlinesRule {
+    fn default() -> Self {
+        NewlinesRule {
+            require_final_newline: true,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LineEndingsRule {
+    pub style: LineEnding,
+    pub level: Severity,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// `\n`
+    Unix,
+    /// `\r\n`
+    Dos,
+    /// Как в первой строке файла
+    Auto,
+}
+
+impl LineEnding {
+    /// Ожидаемый перевод строки для содержимого файла
+    pub fn resolve(self, content: &str) -> &'static str {
+        let dos = match self {
+            LineEnding::Unix => false,
+            LineEnding::Dos => true,
+            LineEnding::Auto => content.split('\n').next().is_some_and(|l| l.ends_with('\r') && l.len() < content.len()),
+        };
+
+        if dos { "\r\n" } else { "\n" }
+    }
+}
+
+impl Default for LineEndingsRule {
+    fn default() -> Self {
+        LineEndingsRule {
+            style: LineEnding::Unix,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyNamingRule {
+    pub convention: NamingConvention,
+    /// Ключи-исключения вроде `apiVersion`; допускаются шаблоны `x-*`
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    pub level: Severity,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum NamingConvention {
+    #[serde(rename = "snake_case")]
+    Snake,
+    #[serde(rename = "camelCase")]
+    Camel,
+    #[serde(rename = "kebab-case")]
+    Kebab,
+    #[serde(rename = "PascalCase")]
+    Pascal,
+}
+
+impl Default for KeyNamingRule {
+    fn default() -> Self {
+        KeyNamingRule {
+            convention: NamingConvention::Snake,
+            ignore: vec![],
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnchorsRule {
+    /// Якорь `&name`, на который нет ни одного `*name`
+    pub forbid_unused: bool,
+    /// Повторное объявление якоря с тем же именем в документе
+    pub forbid_duplicated: bool,
+    /// Ссылка `*name` до объявления якоря или без него
+    pub forbid_undeclared_aliases: bool,
+    pub level: Severity,
+}
+
+impl Default for AnchorsRule {
+    fn default() -> Self {
+        AnchorsRule {
+            forbid_unused: false,
+            forbid_duplicated: false,
+            forbid_undeclared_aliases: true,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OctalValuesRule {
+    /// `0755`: восьмеричное в YAML 1.1, десятичное 755 или строка в других парсерах
+    pub forbid_implicit: bool,
+    /// `0o755` из YAML 1.2, который парсеры YAML 1.1 читают как строку
+    pub forbid_explicit: bool,
+    pub level: Severity,
+}
+
+impl Default for OctalValuesRule {
+    fn default() -> Self {
+        OctalValuesRule {
+            forbid_implicit: true,
+            forbid_explicit: true,
+            level: Severity::Off,
+        }
+    }
+}
+
+/// Записи дробных чисел, которые парсеры понимают по-разному
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FloatValuesRule {
+    /// `.inf`, `-.Inf`
+    pub forbid_inf: bool,
+    /// `.nan`
+    pub forbid_nan: bool,
+    /// `.5` вместо `0.5`
+    pub require_numeral_before_decimal: bool,
+    /// `1e3`, `2.5E-4`
+    pub forbid_scientific_notation: bool,
+    pub level: Severity,
+}
+
+impl Default for FloatValuesRule {
+    fn default() -> Self {
+        FloatValuesRule {
+            forbid_inf: false,
+            forbid_nan: false,
+            require_numeral_before_decimal: false,
+            forbid_scientific_notation: false,
+            level: Severity::Warning,
+        }
+    }
+}
+
+/// Ключи без значения (`key:`), которые читаются как null
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmptyValuesRule {
+    pub forbid_in_block_mappings: bool,
+    pub forbid_in_flow_mappings: bool,
+    pub level: Severity,
+}
+
+impl Default for EmptyValuesRule {
+    fn default() -> Self {
+        EmptyValuesRule {
+            forbid_in_block_mappings: true,
+            forbid_in_flow_mappings: true,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MergeKeysRule {
+    /// Запретить `<<: *anchor`: часть инструментов молча игнорирует такие ключи
+    pub forbid: bool,
+    pub level: Severity,
+}
+
+impl Default for MergeKeysRule {
+    fn default() -> Self {
+        MergeKeysRule {
+            forbid: false,
+            level: Severity::Warning,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BlockScalarChompingRule {
+    /// Заголовок блочного скаляра должен явно задавать `-` или `+`
+    pub require_explicit: bool,
+    pub level: Severity,
+}
+
+impl Default for BlockScalarChompingRule {
+    fn default() -> Self {
+        BlockScalarChompingRule {
+            require_explicit: true,
+            level: Severity::Off,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FloatPrecisionRule {
+    /// Максимум цифр после десятичной точки
+    pub max_decimal_places: usize,
+    pub level: Severity,
+}
+
+impl Default for FloatPrecisionRule {
+    fn default() -> Self {
+        FloatPrecisionRule {
+            max_decimal_places: 10,
+            level: Severity::Warning,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FormatConfig {
     pub auto_fix: bool,
@@ -69,6 +981,16 @@ pub struct SeverityRule {
     pub level: Severity,
 }
 
+impl SeverityRule {
+    pub fn warning() -> Self {
+        SeverityRule { level: Severity::Warning }
+    }
+
+    pub fn off() -> Self {
+        SeverityRule { level: Severity::Off }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Severity {
     #[serde(rename = "error")]
@@ -81,6 +1003,17 @@ pub enum Severity {
     Off,
 }
 
+impl Severity {
+    /// Значения по умолчанию для `level` в конфигурациях, где его не было
+    pub fn error() -> Self {
+        Severity::Error
+    }
+
+    pub fn warning() -> Self {
+        Severity::Warning
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut required_fields = HashMap::new();
@@ -89,15 +1022,20 @@ impl Default for Config {
             vec!["apiVersion".to_string(), "kind".to_string(), "metadata.name".to_string()]
         );
 
+
         Config {
+            extends: None,
             rules: RuleConfig {
                 indentation: IndentationRule {
                     spaces: 2,
                     check_multi_line_strings: true,
+                    forbid_tabs: true,
+                    level: Severity::Error,
                 },
                 line_length: LineLengthRule {
                     max: 120,
                     allow_non_breakable_words: true,
+                    level: Severity::Warning,
                 },
                 trailing_spaces: SeverityRule {
                     level: Severity::Error,
@@ -106,13 +1044,17 @@ impl Default for Config {
                     max_start: 0,
                     max_end: 1,
                     max_consecutive: 2,
+                    level: Severity::Warning,
                 },
                 required_fields: RequiredFieldsRule {
                     paths: required_fields,
+                    content: vec![],
+                    level: Severity::Error,
                 },
                 value_types: ValueTypesRule {
                     strict_numbers: true,
                     check_bool_values: true,
+                    level: Severity::Warning,
                 },
                 duplicates: SeverityRule {
                     level: Severity::Error,
@@ -120,35 +1062,441 @@ impl Default for Config {
                 quotes: QuotesRule {
                     prefer_double: false,
                 },
+                implicit_dates: SeverityRule::warning(),
+                port_range: PortRangeRule::default(),
+                indent_sequence: SeverityRule::warning(),
+                final_line: SeverityRule::off(),
+                block_scalar_style: BlockScalarStyleRule::default(),
+                environment_markers: EnvironmentMarkersRule::default(),
+                stray_comma: SeverityRule::warning(),
+                no_inline_secrets: InlineSecretsRule::default(),
+                homogeneous_values: HomogeneousValuesRule::default(),
+                comments_indentation: CommentsIndentationRule::default(),
+                comments: CommentsRule::default(),
+                duplicate_blocks: DuplicateBlocksRule::default(),
+                document_start: DocumentStartRule::default(),
+                key_ordering: KeyOrderingRule::default(),
+                truthy: TruthyRule::default(),
+                key_occurrence_limit: KeyOccurrenceLimitRule::default(),
+                leading_zeros: SeverityRule::warning(),
+                block_scalar_chomping: BlockScalarChompingRule::default(),
+                float_precision: FloatPrecisionRule::default(),
+                strict_indent: SeverityRule::off(),
+                max_depth: MaxDepthRule::default(),
+                document_start_consistency: SeverityRule::off(),
+                compare_to_defaults: CompareToDefaultsRule::default(),
+                duplicate_named_items: DuplicateNamedItemsRule::default(),
+                key_order_template: KeyOrderTemplateRule::default(),
+                control_chars: SeverityRule::warning(),
+                forbidden_keys: ForbiddenKeysRule::default(),
+                quoted_strings: QuotedStringsRule::default(),
+                colons: ColonsRule::default(),
+                hyphens: HyphensRule::default(),
+                brackets: BracketsRule::default(),
+                braces: BracesRule::default(),
+                commas: CommasRule::default(),
+                new_line_at_end_of_file: NewlinesRule::default(),
+                line_endings: LineEndingsRule::default(),
+                key_naming: KeyNamingRule::default(),
+                anchors: AnchorsRule::default(),
+                octal_values: OctalValuesRule::default(),
+                float_values: FloatValuesRule::default(),
+                empty_values: EmptyValuesRule::default(),
+                merge_keys: MergeKeysRule::default(),
             },
             format: FormatConfig {
                 auto_fix: false,
                 backup_files: true,
                 indent_sequence: true,
             },
-            exclude: vec![
-                "**/node_modules/".to_string(),
-                "**/.git/".to_string(),
-                "**/vendor/".to_string(),
-            ],
+            exclude: default_exclude(),
+            extensions: default_extensions(),
+            yaml_version: YamlVersion::default(),
+            includes: IncludesConfig::default(),
+            rule_scopes: HashMap::new(),
+            template: None,
+            source: None,
         }
     }
 }
 
 impl Config {
-    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
-        let content = fs::read_to_string(path)?;
-        let config = serde_yaml::from_str(&content)?;
+    /// Накладывает конфигурации по порядку, как `extends`: более поздние
+    /// переопределяют значения более ранних. Относительные пути в итоговой
+    /// конфигурации считаются от последнего файла
+    pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> anyhow::Result<Self> {
+        let mut merged = serde_yaml::Value::Null;
+
+        for path in paths {
+            let value = load_extended(path.as_ref(), &mut vec![])?;
+            if merged.is_null() {
+                merged = value;
+            } else {
+                merge_values(&mut merged, value);
+            }
+        }
+
+        let last = paths.last().context("no config files given")?.as_ref();
+        let names: Vec<String> = paths.iter().map(|p| p.as_ref().display().to_string()).collect();
+
+        let mut config: Config = serde_yaml::from_value(merged)
+            .with_context(|| format!("failed to parse config at {}", names.join(", ")))?;
+        config.source = Some(last.to_path_buf());
         Ok(config)
     }
 
-    pub fn should_exclude(&self, path: &str) -> bool {
-        // Простая реализация исключений (можно улучшить с помощью glob)
+    /// Ищет конфигурацию в каталоге `start` и выше, до корня репозитория
+    /// (каталога с `.git`) или файловой системы
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        let start = fs::canonicalize(start).ok()?;
+        let mut dir = if start.is_dir() { start.as_path() } else { start.parent()? };
+
+        loop {
+            if let Some(found) = CONFIG_FILES.iter().map(|name| dir.join(name)).find(|p| p.is_file()) {
+                return Some(found);
+            }
+            if dir.join(".git").exists() {
+                return None;
+            }
+            dir = dir.parent()?;
+        }
+    }
+
+    /// Записывает встроенную конфигурацию; существующий файл перезаписывается только с `force`
+    pub fn write_default(path: &Path, force: bool) -> anyhow::Result<()> {
+        if path.exists() && !force {
+            anyhow::bail!("{} already exists, use --force to overwrite it", path.display());
+        }
+
+        let yaml = serde_yaml::to_string(&Config::default())?;
+        fs::write(path, yaml).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Действующая конфигурация в выбранном формате
+    pub fn render(&self, format: ConfigFormat) -> anyhow::Result<String> {
+        Ok(match format {
+            ConfigFormat::Text => format!("Current configuration:\n{:#?}", self),
+            ConfigFormat::Json => serde_json::to_string_pretty(self)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(self)?,
+        })
+    }
+
+    /// Путь из конфигурации: относительный считается от каталога файла конфигурации
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        match self.source.as_ref().and_then(|source| source.parent()) {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        }
+    }
+
+    /// Компилирует `rule_scopes` в наборы шаблонов для каждого правила
+    pub fn rule_scope_sets(&self) -> anyhow::Result<HashMap<String, GlobSet>> {
+        let mut scopes = HashMap::new();
+
+        for (rule, patterns) in &self.rule_scopes {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                builder.add(Glob::new(&path_glob(pattern))
+                    .with_context(|| format!("invalid scope pattern '{}' for rule {}", pattern, rule))?);
+            }
+            let set = builder.build().with_context(|| format!("failed to compile scope of rule {}", rule))?;
+            scopes.insert(rule.clone(), set);
+        }
+
+        Ok(scopes)
+    }
+
+    /// Файл относится к YAML по списку расширений из профиля
+    pub fn is_yaml_file<P: AsRef<Path>>(&self, path: P) -> bool {
+        path.as_ref()
+            .extension()
+            .is_some_and(|ext| self.extensions.iter().any(|e| ext == e.as_str()))
+    }
+
+    /// Компилирует шаблоны `exclude`. Шаблон исключает и совпавший путь,
+    /// и всё содержимое совпавшего каталога
+    pub fn exclude_set(&self) -> anyhow::Result<GlobSet> {
+        let mut builder = GlobSetBuilder::new();
+
         for pattern in &self.exclude {
-            if path.contains(pattern.trim_end_matches('/').trim_end_matches('*')) {
-                return true;
+            let glob = path_glob(pattern);
+            for variant in [glob.clone(), format!("{}/**", glob)] {
+                builder.add(Glob::new(&variant).with_context(|| format!("invalid exclude pattern '{}'", pattern))?);
             }
         }
-        false
+
+        builder.build().context("failed to compile exclude patterns")
+    }
+}
+
+/// Читает конфигурацию и рекурсивно накладывает её на базовую из `extends`.
+/// `chain` — файлы текущей цепочки наследования, для обнаружения циклов
+fn load_extended(path: &Path, chain: &mut Vec<PathBuf>) -> anyhow::Result<serde_yaml::Value> {
+    let key = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&key) {
+        let cycle: Vec<String> = chain.iter().chain([&key]).map(|p| p.display().to_string()).collect();
+        anyhow::bail!("circular extends: {}", cycle.join(" -> "));
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read config at {}", path.display()))?;
+    let mut value: serde_yaml::Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("failed to parse config at {}", path.display()))?;
+    snake_case_keys(&mut value);
+
+    let Some(base) = value.get("extends").and_then(|v| v.as_str()) else {
+        return Ok(value);
+    };
+
+    let mut merged = match base {
+        "default" => serde_yaml::to_value(Config::default())?,
+        "relaxed" => {
+            let mut preset = serde_yaml::to_value(Config::default())?;
+            merge_values(&mut preset, serde_yaml::from_str(RELAXED_PRESET).expect("bundled preset is valid YAML"));
+            preset
+        }
+        url if url.starts_with("http://") || url.starts_with("https://") => {
+            anyhow::bail!("remote extends is not supported, download {} and extend the local copy", url)
+        }
+        file => {
+            let base_path = path.parent().unwrap_or(Path::new("")).join(file);
+            chain.push(key);
+            let base = load_extended(&base_path, chain)
+                .with_context(|| format!("failed to load base config for {}", path.display()))?;
+            chain.pop();
+            base
+        }
+    };
+
+    merge_values(&mut merged, value);
+    Ok(merged)
+}
+
+/// Приводит имена настроек из `max-end` к `max_end`, как в конфигурациях
+/// Python yamllint. Меняются только имена полей: ключи верхнего уровня,
+/// имена правил и их параметров, секций `format` и `includes`. Ключи
+/// пользовательских маппингов (шаблоны `required_fields.paths`, имена
+/// правил в `rule_scopes`) остаются как есть
+fn snake_case_keys(value: &mut serde_yaml::Value) {
+    fn rename(mapping: &mut serde_yaml::Mapping) {
+        *mapping = std::mem::take(mapping)
+            .into_iter()
+            .map(|(key, value)| match key {
+                serde_yaml::Value::String(name) => (serde_yaml::Value::String(name.replace('-', "_")), value),
+                other => (other, value),
+            })
+            .collect();
+    }
+
+    let Some(config) = value.as_mapping_mut() else { return };
+    rename(config);
+
+    if let Some(rules) = config.get_mut("rules").and_then(|v| v.as_mapping_mut()) {
+        rename(rules);
+        for rule in rules.values_mut().filter_map(|v| v.as_mapping_mut()) {
+            rename(rule);
+        }
+    }
+
+    for section in ["format", "includes"] {
+        if let Some(mapping) = config.get_mut(section).and_then(|v| v.as_mapping_mut()) {
+            rename(mapping);
+        }
+    }
+}
+
+/// Накладывает `overlay` на `base`: маппинги сливаются по ключам,
+/// остальные значения (включая списки) заменяются целиком
+fn merge_values(base: &mut serde_yaml::Value, overlay: serde_yaml::Value) {
+    match (base, overlay) {
+        (serde_yaml::Value::Mapping(base), serde_yaml::Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Обход каталога с учётом .gitignore и `.yamllintignore` в каждом каталоге.
+/// Файл пропускается, если его исключает любой из источников: отрицание
+/// `!path` в `.yamllintignore` не возвращает файлы из `exclude` конфигурации
+pub fn walk(root: &Path) -> ignore::Walk {
+    ignore::WalkBuilder::new(root)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .build()
+}
+
+/// Приводит шаблон пути к виду для globset: шаблон без `/` (например,
+/// `*.tpl.yaml`) совпадает с именем файла на любой глубине, как в .gitignore
+pub fn path_glob(pattern: &str) -> String {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+
+    if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{}", pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovered_config_with_kebab_case_keys_is_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::create_dir_all(dir.path().join("k8s/base")).unwrap();
+        fs::write(
+            dir.path().join(".yamllint.yaml"),
+            "extends: default\nrules:\n  indentation:\n    spaces: 4\n    check-multi-line-strings: true\n  \
+             line-length:\n    max: 100\n    allow-non-breakable-words: false\n\
+             format:\n  indent-sequence: false\n  backup-files: true\n",
+        )
+        .unwrap();
+
+        let found = Config::discover(&dir.path().join("k8s/base")).expect("config is discovered");
+        assert_eq!(found.file_name().unwrap(), ".yamllint.yaml");
+
+        let config = Config::from_files(&[&found]).unwrap();
+        assert_eq!(config.rules.indentation.spaces, 4);
+        assert!(config.rules.indentation.check_multi_line_strings);
+        assert_eq!(config.rules.line_length.max, 100);
+        assert!(!config.format.indent_sequence);
+        assert!(config.format.backup_files);
+        assert_eq!(config.source.as_deref(), Some(found.as_path()));
+    }
+
+    #[test]
+    fn discovery_stops_at_repository_root() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".yamllint.yaml"), "rules: {}\n").unwrap();
+        fs::create_dir_all(dir.path().join("repo/.git")).unwrap();
+
+        assert_eq!(Config::discover(&dir.path().join("repo")), None);
+    }
+
+    #[test]
+    fn bundled_repository_configs_parse() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        for name in [".yamllint.yaml", "config.yaml"] {
+            let config = Config::from_files(&[root.join(name)]);
+            assert!(config.is_ok(), "{}: {:#}", name, config.unwrap_err());
+        }
+    }
+
+    #[test]
+    fn user_mapping_keys_keep_their_dashes() {
+        let mut value: serde_yaml::Value = serde_yaml::from_str(
+            "rule_scopes:\n  line-length: ['**']\nrules:\n  required-fields:\n    paths:\n      docker-compose.yaml: [services]\n",
+        )
+        .unwrap();
+        snake_case_keys(&mut value);
+
+        assert!(value["rule_scopes"].get("line-length").is_some());
+        assert!(value["rules"]["required_fields"]["paths"].get("docker-compose.yaml").is_some());
+    }
+
+    #[test]
+    fn generated_config_respects_the_overwrite_guard() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".yamllint.yaml");
+        fs::write(&path, "edited").unwrap();
+
+        let error = Config::write_default(&path, false).unwrap_err();
+        assert!(error.to_string().contains("--force"), "{:#}", error);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "edited");
+
+        Config::write_default(&path, true).unwrap();
+        assert!(Config::from_files(&[&path]).is_ok());
+    }
+
+    #[test]
+    fn generated_config_goes_to_the_custom_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ci/lint.yaml");
+        fs::create_dir(dir.path().join("ci")).unwrap();
+        let in_cwd = || fs::read(CONFIG_FILES[0]).ok();
+        let before = in_cwd();
+
+        Config::write_default(&path, false).unwrap();
+
+        let generated = Config::from_files(&[&path]).unwrap();
+        assert_eq!(generated.rules.line_length.max, Config::default().rules.line_length.max);
+        assert!(!dir.path().join(CONFIG_FILES[0]).exists());
+        assert_eq!(in_cwd(), before);
+    }
+
+    #[test]
+    fn effective_config_renders_as_json_and_yaml() {
+        let mut config = Config::default();
+        config.rules.line_length.max = 99;
+
+        let json: serde_json::Value = serde_json::from_str(&config.render(ConfigFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["rules"]["line_length"]["max"], 99);
+
+        let yaml: Config = serde_yaml::from_str(&config.render(ConfigFormat::Yaml).unwrap()).unwrap();
+        assert_eq!(yaml.rules.line_length.max, 99);
+    }
+
+    #[test]
+    fn custom_profile_excludes_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = dir.path().join("profile.yaml");
+        fs::write(&profile, "exclude:\n  - generated/\n").unwrap();
+
+        let (exclude, extensions) = Profile::load(Some(profile.to_str().unwrap()));
+        assert_eq!(exclude, vec!["generated/"]);
+        // Ключ, которого нет в профиле организации, берётся из встроенного
+        assert_eq!(extensions, vec!["yaml", "yml"]);
+
+        let config = Config { exclude, extensions, ..Config::default() };
+        let excluded = config.exclude_set().unwrap();
+        assert!(excluded.is_match("charts/generated/values.yaml"));
+        assert!(!excluded.is_match("charts/values.yaml"));
+    }
+
+    #[test]
+    fn unreadable_profile_falls_back_to_bundled() {
+        let bundled = Profile::load(None);
+        assert_eq!(Profile::load(Some("/nonexistent/profile.yaml")), bundled);
+    }
+
+    #[test]
+    fn later_config_files_override_earlier_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.yaml");
+        let local = dir.path().join("local.yaml");
+        fs::write(&base, "extends: default\nrules:\n  line_length:\n    max: 100\n  indentation:\n    spaces: 4\n").unwrap();
+        fs::write(&local, "rules:\n  line-length:\n    max: 140\n").unwrap();
+
+        let config = Config::from_files(&[&base, &local]).unwrap();
+
+        assert_eq!(config.rules.line_length.max, 140);
+        assert_eq!(config.rules.indentation.spaces, 4);
+        assert_eq!(config.source.as_deref(), Some(local.as_path()));
+    }
+
+    #[test]
+    fn exclude_patterns_match_templates_and_test_directories() {
+        let config = Config {
+            exclude: vec!["*.tpl.yaml".to_string(), "**/test/**".to_string()],
+            ..Config::default()
+        };
+        let excluded = config.exclude_set().unwrap();
+
+        for path in ["app.tpl.yaml", "charts/web/values.tpl.yaml", "test/a.yaml", "src/test/fixtures/b.yaml"] {
+            assert!(excluded.is_match(path), "{} should be excluded", path);
+        }
+        for path in ["app.yaml", "tpl.yaml", "contest/a.yaml", "tests/a.yaml", "src/test.yaml"] {
+            assert!(!excluded.is_match(path), "{} should not be excluded", path);
+        }
     }
 }
@@ -0,0 +1,104 @@
+//! Директивы в комментариях, отключающие правила для части файла:
+//! `# yamllint disable`, `# yamllint enable`, `# yamllint disable-line`
+//! и `# yamllint disable-file`, с необязательным списком `rule:<имя>`.
+
+use crate::scanner::{Line, TokenKind};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+lazy_static! {
+    static ref DIRECTIVE_RE: Regex = Regex::new(
+        r"^#\s*yamllint\s+(disable-line|disable-file|disable|enable)((?:\s+rule:\S+)*)\s*$"
+    ).unwrap();
+}
+
+/// Набор отключённых правил
+#[derive(Debug, Clone, Default)]
+struct Scope {
+    /// Отключены все правила, кроме `except`
+    all: bool,
+    rules: HashSet<String>,
+    except: HashSet<String>,
+}
+
+impl Scope {
+    fn disables(&self, rule: &str) -> bool {
+        (self.all && !self.except.contains(rule)) || self.rules.contains(rule)
+    }
+
+    fn disable(&mut self, rules: &[String]) {
+        if rules.is_empty() {
+            *self = Scope { all: true, ..Scope::default() };
+        }
+        for rule in rules {
+            self.except.remove(rule);
+            self.rules.insert(rule.clone());
+        }
+    }
+
+    fn enable(&mut self, rules: &[String]) {
+        if rules.is_empty() {
+            *self = Scope::default();
+        }
+        for rule in rules {
+            self.rules.remove(rule);
+            if self.all {
+                self.except.insert(rule.clone());
+            }
+        }
+    }
+}
+
+pub struct Directives {
+    file_disabled: bool,
+    /// Состояние для каждой строки (индекс с 0)
+    lines: Vec<Scope>,
+    /// `disable-line` для отдельных строк (номер с 1)
+    single: HashMap<usize, Scope>,
+}
+
+impl Directives {
+    pub fn parse(lines: &[Line]) -> Self {
+        let mut directives = Directives { file_disabled: false, lines: vec![], single: HashMap::new() };
+        let mut current = Scope::default();
+
+        for line in lines {
+            let comment = line.tokens.iter().find(|t| t.kind == TokenKind::Comment);
+
+            if let Some(caps) = comment.and_then(|c| DIRECTIVE_RE.captures(c.text)) {
+                let rules: Vec<String> = caps[2]
+                    .split_whitespace()
+                    .filter_map(|r| r.strip_prefix("rule:"))
+                    .map(|r| r.to_string())
+                    .collect();
+
+                match &caps[1] {
+                    "disable" => current.disable(&rules),
+                    "enable" => current.enable(&rules),
+                    "disable-file" => directives.file_disabled = true,
+                    _ => {
+                        // Комментарий на отдельной строке относится к следующей строке
+                        let target = if line.tokens.len() == 1 { line.number + 1 } else { line.number };
+                        directives.single.entry(target).or_default().disable(&rules);
+                    }
+                }
+            }
+
+            directives.lines.push(current.clone());
+        }
+
+        directives
+    }
+
+    /// Ошибки синтаксиса отключить нельзя: без разбора остальные проверки не работают
+    pub fn is_disabled(&self, rule: &str, line: usize) -> bool {
+        if rule == "syntax" {
+            return false;
+        }
+
+        self.file_disabled
+            || self.lines.get(line.wrapping_sub(1)).is_some_and(|s| s.disables(rule))
+            || self.single.get(&line).is_some_and(|s| s.disables(rule))
+    }
+}
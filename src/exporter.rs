@@ -0,0 +1,619 @@
+//! Экспорт результатов проверки в машиночитаемые форматы.
+
+use crate::config::Severity;
+use crate::linter::LintReport;
+use crate::rules::{self, LintResult};
+use clap::ValueEnum;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Человекочитаемый вывод в терминал
+    Text,
+    Json,
+    /// SARIF 2.1.0 для GitHub code scanning и Azure DevOps
+    Sarif,
+    /// Отчёт GitLab Code Quality
+    Gitlab,
+    /// Checkstyle XML для Jenkins и других CI
+    Checkstyle,
+    /// Таблица находок для разбора в электронных таблицах
+    Csv,
+    /// Самодостаточная HTML-страница для артефактов CI
+    Html,
+    /// Формат движков CodeClimate
+    Codeclimate,
+    /// Диагностики LSP по файлам для плагинов редакторов
+    Lsp,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ExportSummary {
+    pub files: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportData<'a> {
+    files: &'a [LintReport],
+    summary: ExportSummary,
+}
+
+/// JSON-отчёт, прочитанный обратно; сводка пересчитывается заново
+#[derive(Debug, Deserialize)]
+struct ImportData {
+    files: Vec<LintReport>,
+}
+
+/// Читает отчёты, сохранённые в формате `-O json`
+pub fn read_json_reports<P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<LintReport>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let data: ImportData = serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse JSON report {}", path.display()))?;
+
+    Ok(data.files)
+}
+
+/// Объединяет отчёты шардов. Файл, проверенный в нескольких шардах, —
+/// ошибка: иначе его находки попали бы в сводку дважды
+pub fn merge_reports<P: AsRef<Path>>(paths: &[P]) -> anyhow::Result<Vec<LintReport>> {
+    let mut merged = vec![];
+    let mut sources: HashMap<String, &Path> = HashMap::new();
+
+    for path in paths {
+        let path = path.as_ref();
+        for report in read_json_reports(path)? {
+            if let Some(first) = sources.insert(report.file.clone(), path) {
+                anyhow::bail!("{} is reported in both {} and {}", report.file, first.display(), path.display());
+            }
+            merged.push(report);
+        }
+    }
+
+    Ok(merged)
+}
+
+pub struct Exporter<'a> {
+    reports: &'a [LintReport],
+}
+
+impl<'a> Exporter<'a> {
+    pub fn new(reports: &'a [LintReport]) -> Self {
+        Exporter { reports }
+    }
+
+    pub fn export(&self, format: OutputFormat) -> anyhow::Result<String> {
+        match format {
+            OutputFormat::Text => anyhow::bail!("text output is printed by the linter, not exported"),
+            OutputFormat::Json => self.to_json(),
+            OutputFormat::Sarif => self.to_sarif(),
+            OutputFormat::Gitlab => self.to_gitlab(),
+            OutputFormat::Checkstyle => self.to_checkstyle(),
+            OutputFormat::Csv => self.to_csv(),
+            OutputFormat::Html => self.to_html(),
+            OutputFormat::Codeclimate => self.to_codeclimate(),
+            OutputFormat::Lsp => self.to_lsp(),
+        }
+    }
+
+    pub fn summary(&self) -> ExportSummary {
+        let results = self.reports.iter().flat_map(|r| &r.results);
+
+        ExportSummary {
+            files: self.reports.len(),
+            errors: results.clone().filter(|r| r.is_error()).count(),
+            warnings: results.filter(|r| r.is_warning()).count(),
+        }
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        let data = ExportData { files: self.reports, summary: self.summary() };
+        Ok(serde_json::to_string_pretty(&data)?)
+    }
+
+    pub fn to_sarif(&self) -> anyhow::Result<String> {
+        let rule_ids: Vec<&str> = self
+            .reports
+            .iter()
+            .flat_map(|r| &r.results)
+            .map(|r| r.rule.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let rules: Vec<_> = rule_ids
+            .iter()
+            .map(|id| {
+                let info = rules::rule_info(id);
+                json!({
+                    "id": id,
+                    "shortDescription": { "text": if info.description.is_empty() { *id } else { info.description } },
+                    "help": { "text": info.suggestion },
+                })
+            })
+            .collect();
+
+        let root = std::env::current_dir().ok();
+        let mut results = vec![];
+        for report in self.reports {
+            for result in &report.results {
+                let level = match result.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "note",
+                    Severity::Off => continue,
+                };
+
+                results.push(json!({
+                    "ruleId": result.rule,
+                    "ruleIndex": rule_ids.iter().position(|id| *id == result.rule),
+                    "level": level,
+                    "message": { "text": result.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": sarif_artifact(&report.file, root.as_deref()),
+                            "region": {
+                                "startLine": result.line,
+                                "startColumn": result.column,
+                            },
+                        },
+                    }],
+                }));
+            }
+        }
+
+        let mut run = json!({
+            "tool": {
+                "driver": {
+                    "name": "yamllint",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        });
+        if let Some(root) = &root {
+            run["originalUriBaseIds"] = json!({
+                SARIF_SRCROOT: { "uri": format!("{}/", file_uri(root).trim_end_matches('/')) },
+            });
+        }
+
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [run],
+        });
+
+        Ok(serde_json::to_string_pretty(&sarif)?)
+    }
+
+    pub fn to_gitlab(&self) -> anyhow::Result<String> {
+        let mut issues = vec![];
+
+        for report in self.reports {
+            for result in &report.results {
+                let Some(severity) = issue_severity(&result.severity) else { continue };
+
+                issues.push(json!({
+                    "description": result.message,
+                    "check_name": result.rule,
+                    "fingerprint": issue_fingerprint(&report.file, result),
+                    "severity": severity,
+                    "location": {
+                        "path": artifact_uri(&report.file),
+                        "lines": { "begin": result.line },
+                    },
+                }));
+            }
+        }
+
+        Ok(serde_json::to_string_pretty(&issues)?)
+    }
+
+    /// Тот же отчёт, что для GitLab, с полями спецификации CodeClimate:
+    /// `type` и `categories`
+    pub fn to_codeclimate(&self) -> anyhow::Result<String> {
+        let mut issues = vec![];
+
+        for report in self.reports {
+            for result in &report.results {
+                let Some(severity) = issue_severity(&result.severity) else { continue };
+
+                issues.push(json!({
+                    "type": "issue",
+                    "check_name": result.rule,
+                    "description": result.message,
+                    "categories": ["Style"],
+                    "severity": severity,
+                    "fingerprint": issue_fingerprint(&report.file, result),
+                    "location": {
+                        "path": artifact_uri(&report.file),
+                        "lines": { "begin": result.line, "end": result.line },
+                    },
+                }));
+            }
+        }
+
+        Ok(serde_json::to_string_pretty(&issues)?)
+    }
+
+    /// `[{"file": ..., "diagnostics": [Diagnostic]}]`; файлы без находок тоже
+    /// перечисляются, чтобы редактор снял старые диагностики. Позиции с 0,
+    /// символы считаются в символах Unicode, а не в UTF-16
+    pub fn to_lsp(&self) -> anyhow::Result<String> {
+        let mut files = vec![];
+
+        for report in self.reports {
+            let mut diagnostics = vec![];
+
+            for result in &report.results {
+                let severity = match result.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                    Severity::Info => 3,
+                    Severity::Off => continue,
+                };
+
+                let line = result.line.saturating_sub(1);
+                let start = result.column.saturating_sub(1);
+                // Без длины фрагмента диагностика тянется до конца строки
+                let end = match result.length {
+                    0 => result.snippet.chars().count().max(start),
+                    length => start + length,
+                };
+
+                diagnostics.push(json!({
+                    "range": {
+                        "start": { "line": line, "character": start },
+                        "end": { "line": line, "character": end },
+                    },
+                    "severity": severity,
+                    "source": "yamllint",
+                    "code": result.rule,
+                    "message": result.message,
+                }));
+            }
+
+            files.push(json!({ "file": report.file, "diagnostics": diagnostics }));
+        }
+
+        Ok(serde_json::to_string_pretty(&files)?)
+    }
+
+    pub fn to_checkstyle(&self) -> anyhow::Result<String> {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<checkstyle version=\"4.3\">\n");
+
+        for report in self.reports {
+            xml.push_str(&format!("  <file name=\"{}\">\n", escape_xml(&report.file)));
+
+            for result in &report.results {
+                let severity = match result.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "info",
+                    Severity::Off => continue,
+                };
+
+                xml.push_str(&format!(
+                    "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"yamllint.{}\"/>\n",
+                    result.line,
+                    result.column,
+                    severity,
+                    escape_xml(&result.message),
+                    escape_xml(&result.rule),
+                ));
+            }
+
+            xml.push_str("  </file>\n");
+        }
+
+        xml.push_str("</checkstyle>");
+        Ok(xml)
+    }
+
+    pub fn to_csv(&self) -> anyhow::Result<String> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.write_record(["file", "line", "column", "severity", "rule", "message"])?;
+
+        for report in self.reports {
+            for result in &report.results {
+                let severity = match result.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "info",
+                    Severity::Off => continue,
+                };
+
+                writer.write_record([
+                    report.file.as_str(),
+                    &result.line.to_string(),
+                    &result.column.to_string(),
+                    severity,
+                    &result.rule,
+                    &result.message,
+                ])?;
+            }
+        }
+
+        // Как и у остальных форматов, перевод строки в конце добавляет вывод
+        let csv = String::from_utf8(writer.into_inner()?)?;
+        Ok(csv.trim_end_matches('\n').to_string())
+    }
+
+    /// Стили и сортировка встроены в страницу, внешних ресурсов нет
+    pub fn to_html(&self) -> anyhow::Result<String> {
+        let summary = self.summary();
+        let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>yamllint report</title>\n");
+        html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", HTML_STYLE));
+
+        html.push_str("<h1>yamllint report</h1>\n");
+        html.push_str(&format!(
+            "<p class=\"summary\">Files: {} &middot; <span class=\"badge error\">{} errors</span> \
+             <span class=\"badge warning\">{} warnings</span></p>\n",
+            summary.files, summary.errors, summary.warnings,
+        ));
+
+        for report in self.reports.iter().filter(|r| !r.results.is_empty()) {
+            html.push_str(&format!("<h2>{}</h2>\n", escape_xml(&report.file)));
+            html.push_str("<table>\n<thead><tr><th>Line</th><th>Column</th><th>Severity</th><th>Rule</th><th>Message</th></tr></thead>\n<tbody>\n");
+
+            for result in &report.results {
+                let severity = match result.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "info",
+                    Severity::Off => continue,
+                };
+
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td><span class=\"badge {severity}\">{severity}</span></td><td>{}</td><td>{}</td></tr>\n",
+                    result.line,
+                    result.column,
+                    escape_xml(&result.rule),
+                    escape_xml(&result.message),
+                ));
+            }
+
+            html.push_str("</tbody>\n</table>\n");
+        }
+
+        if summary.errors == 0 && summary.warnings == 0 {
+            html.push_str("<p>All checks passed!</p>\n");
+        }
+
+        html.push_str(&format!("<script>{}</script>\n</body>\n</html>", HTML_SCRIPT));
+        Ok(html)
+    }
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;width:100%;margin-bottom:2em}\
+th,td{border:1px solid #ddd;padding:4px 8px;text-align:left}\
+th{background:#f4f4f4;cursor:pointer;user-select:none}\
+.badge{border-radius:3px;padding:1px 6px;color:#fff;font-size:0.9em}\
+.error{background:#c62828}.warning{background:#ef8c00}.info{background:#1565c0}";
+
+/// Щелчок по заголовку сортирует таблицу по столбцу; числа сравниваются как числа
+const HTML_SCRIPT: &str = "\
+document.querySelectorAll('th').forEach(function(th){th.addEventListener('click',function(){\
+var table=th.closest('table'),body=table.tBodies[0],i=th.cellIndex,asc=th.dataset.order!=='asc';\
+table.querySelectorAll('th').forEach(function(h){delete h.dataset.order});th.dataset.order=asc?'asc':'desc';\
+Array.from(body.rows).sort(function(a,b){var x=a.cells[i].textContent,y=b.cells[i].textContent;\
+var r=isNaN(x)||isNaN(y)?x.localeCompare(y):x-y;return asc?r:-r}).forEach(function(row){body.appendChild(row)})})});";
+
+/// Итог проверки для обёрток: `{"exit_code": 1, "errors": 3, ...}`
+pub fn exit_json(summary: &ExportSummary, exit_code: i32) -> String {
+    json!({
+        "exit_code": exit_code,
+        "errors": summary.errors,
+        "warnings": summary.warnings,
+        "files": summary.files,
+    })
+    .to_string()
+}
+
+/// Уровень находки в отчётах GitLab и CodeClimate
+fn issue_severity(severity: &Severity) -> Option<&'static str> {
+    match severity {
+        Severity::Error => Some("major"),
+        Severity::Warning => Some("minor"),
+        Severity::Info => Some("info"),
+        Severity::Off => None,
+    }
+}
+
+/// Отпечаток находки, по которому CI сопоставляет её между запусками
+fn issue_fingerprint(file: &str, result: &LintResult) -> String {
+    fingerprint(&[file, &result.line.to_string(), &result.rule, &result.message])
+}
+
+/// Стабильный между запусками и версиями хеш (FNV-1a, 64 бита):
+/// `DefaultHasher` не гарантирует одинаковый результат в разных сборках
+fn fingerprint(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+
+    for part in parts {
+        // Разделитель, чтобы ("ab", "c") и ("a", "bc") давали разный хеш
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// Экранирует текст для значения атрибута XML
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Путь файла в отчётах: относительный, с прямыми слешами
+fn artifact_uri(file: &str) -> String {
+    file.trim_start_matches("./").replace('\\', "/")
+}
+
+/// Базовый каталог SARIF, относительно которого заданы пути файлов
+const SARIF_SRCROOT: &str = "%SRCROOT%";
+
+/// `artifactLocation` для SARIF: путь относительно `%SRCROOT%` (текущего
+/// каталога), а файлы вне него — абсолютным `file://` URI
+fn sarif_artifact(file: &str, root: Option<&Path>) -> serde_json::Value {
+    let path = Path::new(file);
+    let relative = if path.is_absolute() {
+        root.and_then(|root| path.strip_prefix(root).ok())
+    } else {
+        Some(path)
+    };
+
+    match relative {
+        Some(relative) => json!({
+            "uri": percent_encode(&artifact_uri(&relative.to_string_lossy()), "/"),
+            "uriBaseId": SARIF_SRCROOT,
+        }),
+        None => json!({ "uri": file_uri(path) }),
+    }
+}
+
+/// Абсолютный путь как `file:///...` URI
+fn file_uri(path: &Path) -> String {
+    let path = artifact_uri(&path.to_string_lossy());
+    let slash = if path.starts_with('/') { "" } else { "/" };
+    format!("file://{}{}", slash, percent_encode(&path, "/:"))
+}
+
+/// Кодирует всё, кроме незарезервированных символов RFC 3986 и `keep`
+fn percent_encode(text: &str, keep: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) || keep.as_bytes().contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::linter::YamlLinter;
+
+    fn lint_fixtures(dir: &Path) -> Vec<LintReport> {
+        let files = [
+            ("clean.yaml", "---\nkey: value\n"),
+            ("warning.yaml", "---\ndate: 2023-01-01\n"),
+            ("error.yaml", "---\nkey: value\nkey: other\n"),
+        ];
+        let linter = YamlLinter::new(Config::default()).unwrap();
+
+        files
+            .iter()
+            .map(|(name, content)| {
+                let path = dir.join(name);
+                fs::write(&path, content).unwrap();
+                linter.lint_file(&path).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn merged_shards_match_a_single_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let single = lint_fixtures(dir.path());
+
+        let shards = [dir.path().join("a.json"), dir.path().join("b.json")];
+        fs::write(&shards[0], Exporter::new(&single[..2]).to_json().unwrap()).unwrap();
+        fs::write(&shards[1], Exporter::new(&single[2..]).to_json().unwrap()).unwrap();
+
+        let merged = merge_reports(&shards).unwrap();
+
+        assert_eq!(Exporter::new(&merged).summary(), Exporter::new(&single).summary());
+        let state = |reports: &[LintReport]| -> Vec<(String, bool)> {
+            reports.iter().map(|r| (r.file.clone(), r.passed)).collect()
+        };
+        assert_eq!(state(&merged), state(&single));
+        assert!(merged.iter().any(|r| !r.passed));
+    }
+
+    #[test]
+    fn file_in_two_shards_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let single = lint_fixtures(dir.path());
+
+        let shards = [dir.path().join("a.json"), dir.path().join("b.json")];
+        fs::write(&shards[0], Exporter::new(&single[..2]).to_json().unwrap()).unwrap();
+        fs::write(&shards[1], Exporter::new(&single[1..]).to_json().unwrap()).unwrap();
+
+        let error = merge_reports(&shards).unwrap_err();
+        assert!(error.to_string().contains("warning.yaml"), "{:#}", error);
+    }
+
+    #[test]
+    fn exit_json_matches_findings() {
+        let dir = tempfile::tempdir().unwrap();
+        let reports = lint_fixtures(dir.path());
+        let results: Vec<&LintResult> = reports.iter().flat_map(|r| &r.results).collect();
+
+        let summary = Exporter::new(&reports).summary();
+        let line: serde_json::Value = serde_json::from_str(&exit_json(&summary, 1)).unwrap();
+
+        assert_eq!(line["exit_code"], 1);
+        assert_eq!(line["files"], 3);
+        assert_eq!(line["errors"], results.iter().filter(|r| r.is_error()).count());
+        assert_eq!(line["warnings"], results.iter().filter(|r| r.is_warning()).count());
+        assert!(summary.errors > 0 && summary.warnings > 0);
+    }
+
+    #[test]
+    fn sarif_output_matches_the_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut reports = lint_fixtures(dir.path());
+        reports[1].file = "configs/my warning.yaml".to_string();
+
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/sarif-schema-2.1.0.json")).unwrap();
+        let sarif: serde_json::Value = serde_json::from_str(&Exporter::new(&reports).to_sarif().unwrap()).unwrap();
+
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let errors: Vec<String> = validator.iter_errors(&sarif).map(|e| format!("{}: {}", e.instance_path(), e)).collect();
+        assert!(errors.is_empty(), "{:#?}", errors);
+
+        let locations: Vec<&serde_json::Value> = sarif["runs"][0]["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["locations"][0]["physicalLocation"]["artifactLocation"])
+            .collect();
+        assert!(locations.iter().any(|l| l["uri"] == "configs/my%20warning.yaml" && l["uriBaseId"] == "%SRCROOT%"));
+        assert!(locations.iter().all(|l| l["uri"].as_str().unwrap().starts_with("file:///") || l["uriBaseId"] == "%SRCROOT%"));
+        assert!(sarif["runs"][0]["originalUriBaseIds"]["%SRCROOT%"]["uri"].as_str().unwrap().ends_with('/'));
+    }
+}
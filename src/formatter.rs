@@ -1,86 +1,354 @@
-use crate::config::Config;
-use crate::linter::LintReport;
+use crate::config::{self, Config, Severity};
+use crate::linter::{self, LintReport};
+use crate::scanner::{self, ScalarStyle, TokenKind};
+use anyhow::Context;
 use regex::Regex;
+use serde::Deserialize;
+use serde_yaml::Value;
+use similar::TextDiff;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Число строк, изменённых исправлениями каждого правила. Одна строка может
+/// содержать несколько находок, поэтому с числом находок оно не совпадает
+pub type FixCounts = BTreeMap<&'static str, usize>;
+
+/// Исходное и отформатированное содержимое изменившегося файла
+type Changed = (String, String);
+
+pub struct FixOutcome {
+    pub content: String,
+    pub fixes: FixCounts,
+    /// Причина, по которой исправления отброшены и содержимое оставлено как есть
+    pub rejected: Option<String>,
+}
+
+/// Итог `--fix`: исправления по правилам и пути действительно изменённых файлов
+#[derive(Debug, Default)]
+pub struct FixReport {
+    pub fixes: FixCounts,
+    pub modified: Vec<String>,
+}
+
+/// Исправляет файлы с находками. Файлы с синтаксической ошибкой пропускаются,
+/// если не задан `fix_broken`: текстовые исправления могут их только испортить
+pub fn auto_fix_files(reports: &[LintReport], config: &Config, fix_broken: bool) -> anyhow::Result<FixReport> {
+    let mut total = FixReport::default();
 
-pub fn auto_fix_files(reports: &[LintReport], config: &Config) -> anyhow::Result<()> {
     for report in reports {
-        if !report.results.is_empty() {
-            auto_fix_file(&report.file, config)?;
+        if !fix_broken && is_broken(report) {
+            eprintln!("Skipped {}: syntax error, use --fix-broken to fix it anyway", report.file);
+            continue;
+        }
+
+        if report.results.is_empty() || !is_fixable(report, fix_broken) {
+            continue;
+        }
+
+        if let Some(fixes) = auto_fix_file(&report.file, config)? {
+            for (rule, count) in fixes {
+                *total.fixes.entry(rule).or_insert(0) += count;
+            }
+            total.modified.push(report.file.clone());
         }
     }
-    Ok(())
+
+    Ok(total)
 }
 
-pub fn auto_fix_file<P: AsRef<Path>>(path: P, config: &Config) -> anyhow::Result<()> {
+/// Файл с синтаксической ошибкой: текстовые исправления могут его только испортить
+pub fn is_broken(report: &LintReport) -> bool {
+    report.results.iter().any(|r| r.rule == "syntax")
+}
+
+/// Отчёт можно исправлять: это не путь из --files-from, который не удалось
+/// проверить, и не сломанный файл (если не задан `fix_broken`)
+pub fn is_fixable(report: &LintReport, fix_broken: bool) -> bool {
+    let unreadable = report.results.iter().any(|r| r.rule == linter::FILE_LIST_RULE);
+    !unreadable && (fix_broken || !is_broken(report))
+}
+
+/// Сводка исправлений по изменённым строкам, только для сработавших правил
+pub fn fix_summary(fixes: &FixCounts) -> Option<String> {
+    let changed: Vec<String> = fixes
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .map(|(rule, count)| format!("{}: {} line(s) changed", rule, count))
+        .collect();
+
+    (!changed.is_empty()).then(|| format!("Fix summary: {}", changed.join(", ")))
+}
+
+/// Список изменённых файлов для `--print-fixed`: по пути на строке или JSON-массив
+pub fn modified_list(files: &[String], json: bool) -> String {
+    if json {
+        return serde_json::to_string(files).unwrap_or_default();
+    }
+
+    files.iter().map(|file| format!("{}\n", file)).collect()
+}
+
+/// Исправляет файл на месте; `None`, если содержимое не изменилось
+pub fn auto_fix_file<P: AsRef<Path>>(path: P, config: &Config) -> anyhow::Result<Option<FixCounts>> {
     let path = path.as_ref();
-    let content = fs::read_to_string(path)?;
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    let outcome = fix_content(&content, config);
+
+    if let Some(reason) = &outcome.rejected {
+        eprintln!("Warning: left {} unchanged: {}", path.display(), reason);
+        return Ok(None);
+    }
 
-    let fixed_content = fix_content(&content, config);
+    // Нетронутые файлы не перезаписываем и не перечисляем
+    if outcome.content == content {
+        return Ok(None);
+    }
 
     if config.format.backup_files {
-        let backup_path = path.with_extension("yaml.bak");
-        fs::copy(path, backup_path)?;
+        let backup_path = backup_path(path);
+        fs::copy(path, &backup_path)
+            .with_context(|| format!("failed to write backup {}", backup_path.display()))?;
     }
 
-    fs::write(path, fixed_content)?;
-    println!("Fixed: {}", path.display());
+    fs::write(path, outcome.content)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(Some(outcome.fixes))
+}
+
+/// Исправленное содержимое без подсчёта исправлений
+pub fn format_content(content: &str, name: &str, config: &Config) -> String {
+    let outcome = fix_content(content, config);
 
-    Ok(())
+    if let Some(reason) = &outcome.rejected {
+        eprintln!("Warning: left {} unchanged: {}", name, reason);
+    }
+
+    outcome.content
 }
 
-fn fix_content(content: &str, config: &Config) -> String {
+/// Предпросмотр `check --diff`: патчи для проверенных файлов, которые изменились бы.
+/// Пропускаются те же файлы, что и при `--fix`
+pub fn diff_reports(reports: &[LintReport], config: &Config) -> Vec<String> {
+    let mut patches = vec![];
+
+    for report in reports {
+        if !is_fixable(report, false) {
+            if is_broken(report) {
+                eprintln!("Skipped {}: syntax error", report.file);
+            }
+            continue;
+        }
+
+        let formatted = format_content(&report.source, &report.file, config);
+        let patch = unified_diff(&report.source, &formatted, &report.file);
+        if !patch.is_empty() {
+            patches.push(patch);
+        }
+    }
+
+    patches
+}
+
+/// Unified diff между исходным и исправленным текстом; пустая строка — без изменений
+pub fn unified_diff(original: &str, formatted: &str, name: &str) -> String {
+    if original == formatted {
+        return String::new();
+    }
+
+    let name = name.trim_start_matches("./");
+    TextDiff::from_lines(original, formatted)
+        .unified_diff()
+        .header(&format!("a/{}", name), &format!("b/{}", name))
+        .to_string()
+}
+
+fn fix_content(content: &str, config: &Config) -> FixOutcome {
     let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let mut fixes = FixCounts::new();
 
     // 1. Исправление отступов
-    fix_indentation(&mut lines, config);
+    fixes.insert("indentation", fix_indentation(&mut lines, config));
+
+    // 2. Отступ последовательностей относительно ключа
+    fixes.insert("indent-sequence", fix_sequence_indentation(&mut lines, config));
+
+    // 3. Удаление trailing spaces
+    fixes.insert("trailing-spaces", fix_trailing_spaces(&mut lines));
+
+    // 4. Исправление пустых строк
+    fixes.insert("empty-lines", fix_empty_lines(&mut lines, config));
+
+    // 5. Форматирование кавычек
+    fixes.insert("quotes", fix_quotes(&mut lines, config));
+
+    // 6. Маркер начала документа
+    fixes.insert("document-start", fix_document_start(&mut lines, config));
+
+    // 7. Пробелы вокруг двоеточий
+    fixes.insert("colons", fix_colons(&mut lines, config));
+
+    // 8. Пробелы после `-` в элементах последовательностей
+    fixes.insert("hyphens", fix_hyphens(&mut lines, config));
+
+    // 9. Пробелы вокруг запятых в flow-коллекциях
+    fixes.insert("commas", fix_commas(&mut lines, config));
 
-    // 2. Удаление trailing spaces
-    fix_trailing_spaces(&mut lines);
+    // 10. Переводы строк: `lines()` отбрасывает `\r`, поэтому они выбираются заново
+    let endings = &config.rules.line_endings;
+    let eol = if endings.level == Severity::Off { "\n" } else { endings.style.resolve(content) };
+    let converted = content
+        .split_inclusive('\n')
+        .filter(|line| line.ends_with('\n') && line.ends_with("\r\n") != (eol == "\r\n"))
+        .count();
+    fixes.insert("line-endings", converted);
 
-    // 3. Исправление пустых строк
-    fix_empty_lines(&mut lines, config);
+    // 11. Добавляем финальную новую строку, если правило её не отключает.
+    // Пустой файл правило принимает, поэтому он остаётся пустым
+    let newline = &config.rules.new_line_at_end_of_file;
+    let keep_missing = lines.is_empty()
+        || (newline.level != Severity::Off && !newline.require_final_newline && !content.ends_with('\n'));
+    let fixed = if keep_missing { lines.join(eol) } else { lines.join(eol) + eol };
 
-    // 4. Форматирование кавычек
-    fix_quotes(&mut lines, config);
+    // 12. Исправления не должны ломать разбор или менять данные документа
+    if let Some(reason) = check_fixed(content, &fixed) {
+        return FixOutcome { content: content.to_string(), fixes: FixCounts::new(), rejected: Some(reason) };
+    }
+
+    FixOutcome { content: fixed, fixes, rejected: None }
+}
+
+/// Сравнивает документы до и после исправления. Файл, который не разбирался
+/// и до исправлений (`--fix-broken`), проверить не на чем — он пропускается
+fn check_fixed(original: &str, fixed: &str) -> Option<String> {
+    let parse = |content: &str| -> Result<Vec<Value>, serde_yaml::Error> {
+        serde_yaml::Deserializer::from_str(content).map(Value::deserialize).collect()
+    };
 
-    // 5. Добавляем финальную новую строку
-    lines.join("\n") + "\n"
+    let before = parse(original).ok()?;
+
+    match parse(fixed) {
+        Err(e) => Some(format!("the fixed content would not parse: {}", e)),
+        Ok(after) if after != before => Some("the fixes would change the document data".to_string()),
+        Ok(_) => None,
+    }
 }
 
-fn fix_indentation(lines: &mut [String], config: &Config) {
+fn fix_indentation(lines: &mut [String], config: &Config) -> usize {
     let expected_spaces = config.rules.indentation.spaces;
+    let mut fixed = 0;
 
     for line in lines.iter_mut() {
         if line.trim().is_empty() {
             continue;
         }
 
+        // Табуляция в отступе заменяется на заданное число пробелов
+        if config.rules.indentation.forbid_tabs && line.starts_with([' ', '\t']) {
+            let content = line.trim_start_matches([' ', '\t']);
+            let indent = &line[..line.len() - content.len()];
+            if indent.contains('\t') {
+                *line = indent.replace('\t', &" ".repeat(expected_spaces)) + content;
+                fixed += 1;
+            }
+        }
+
         if line.starts_with(' ') {
             let leading_spaces = line.len() - line.trim_start().len();
             let new_indent = (leading_spaces / expected_spaces) * expected_spaces;
             let new_line = " ".repeat(new_indent) + line.trim_start();
-            *line = new_line;
+            if *line != new_line {
+                *line = new_line;
+                fixed += 1;
+            }
         }
     }
+
+    fixed
+}
+
+fn fix_sequence_indentation(lines: &mut [String], config: &Config) -> usize {
+    let text = lines.join("\n");
+    let scanned = scanner::scan(&text);
+    let sequences = scanner::key_sequences(&scanned);
+    let spaces = config.rules.indentation.spaces;
+
+    // Снизу вверх: вложенные блоки сдвигаются раньше охватывающих,
+    // поэтому их взаимное расположение сохраняется
+    let mut shifts = vec![];
+    for seq in sequences.iter().rev() {
+        let indented = seq.dash_offset > seq.key_offset;
+        if indented == config.format.indent_sequence {
+            continue;
+        }
+
+        // Блок последовательности: до первой строки с отступом не больше ключа,
+        // не считая элементов `-` на уровне ключа
+        let mut end = seq.line_index + 1;
+        while end < lines.len() {
+            let line = &lines[end];
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            let is_item = indent == seq.dash_offset && line[indent..].starts_with('-');
+            if !line.trim().is_empty() && indent <= seq.key_offset && !is_item {
+                break;
+            }
+            end += 1;
+        }
+
+        shifts.push((seq.line_index, end, seq.dash_offset - seq.key_offset));
+    }
+
+    let fixed = shifts.len();
+
+    for (start, end, excess) in shifts {
+        for line in lines[start..end].iter_mut() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if config.format.indent_sequence {
+                line.insert_str(0, &" ".repeat(spaces));
+            } else {
+                let indent = line.len() - line.trim_start_matches(' ').len();
+                line.drain(..excess.min(indent));
+            }
+        }
+    }
+
+    fixed
 }
 
-fn fix_trailing_spaces(lines: &mut [String]) {
+fn fix_trailing_spaces(lines: &mut [String]) -> usize {
+    let mut fixed = 0;
+
     for line in lines.iter_mut() {
-        *line = line.trim_end().to_string();
+        let trimmed = line.trim_end();
+        if trimmed.len() != line.len() {
+            *line = trimmed.to_string();
+            fixed += 1;
+        }
     }
+
+    fixed
 }
 
-fn fix_empty_lines(lines: &mut Vec<String>, config: &Config) {
+fn fix_empty_lines(lines: &mut Vec<String>, config: &Config) -> usize {
+    let mut removed = 0;
+
     // Удаляем пустые строки в начале
     while !lines.is_empty() && lines[0].trim().is_empty() {
         lines.remove(0);
+        removed += 1;
     }
 
     // Удаляем пустые строки в конце
     while !lines.is_empty() && lines.last().unwrap().trim().is_empty() {
         lines.pop();
+        removed += 1;
     }
 
     // Удаляем лишние последовательные пустые строки
@@ -98,6 +366,7 @@ fn fix_empty_lines(lines: &mut Vec<String>, config: &Config) {
             if consecutive > config.rules.empty_lines.max_consecutive {
                 let to_remove = consecutive - config.rules.empty_lines.max_consecutive;
                 lines.drain(i..i + to_remove);
+                removed += to_remove;
                 i += config.rules.empty_lines.max_consecutive;
             } else {
                 i = j;
@@ -107,62 +376,494 @@ fn fix_empty_lines(lines: &mut Vec<String>, config: &Config) {
         }
     }
 
-    // Добавляем одну пустую строку в конце, если нужно
-    if config.rules.empty_lines.max_end > 0 {
-        lines.push("".to_string());
+    // Перевод строки в конце файла добавляет fix_content, пустая строка
+    // здесь дала бы лишнюю пустую строку в конце
+    removed
+}
+
+fn fix_document_start(lines: &mut Vec<String>, config: &Config) -> usize {
+    let rule = &config.rules.document_start;
+
+    if rule.level == Severity::Off {
+        return 0;
     }
+
+    let text = lines.join("\n");
+    let scanned = scanner::scan(&text);
+    let Some(first) = scanner::first_content_line(&scanned) else {
+        return 0;
+    };
+    let has_marker = scanned[first].tokens[0].kind == TokenKind::DocumentStart;
+
+    match (rule.present, has_marker) {
+        // Маркер ставится после ведущих комментариев и директив
+        (true, false) => lines.insert(first, "---".to_string()),
+        (false, true) => {
+            // `--- value`: значение и комментарий остаются на своей строке
+            let rest = lines[first][3..].trim_start().to_string();
+            if rest.is_empty() {
+                lines.remove(first);
+            } else {
+                lines[first] = rest;
+            }
+        }
+        _ => return 0,
+    }
+
+    1
 }
 
-fn fix_quotes(lines: &mut [String], config: &Config) {
-    let re = Regex::new(r#""([^"]*)"|'([^']*)'"#).unwrap();
+/// Нормализует кавычки скаляров по токенам сканера, поэтому кавычки,
+/// `:` и `#` внутри значений не затрагиваются. С `prefer_double` строки
+/// в одинарных кавычках переводятся в двойные, иначе кавычки снимаются
+/// только с простых строк, которые без них не поменяют тип
+fn fix_quotes(lines: &mut [String], config: &Config) -> usize {
+    // Без кавычек остаётся строкой в YAML 1.1 и 1.2: не число, не bool, не null
+    let plain_safe = Regex::new(r"^[A-Za-z_][A-Za-z0-9_./-]*$").unwrap();
+    let reserved = ["y", "n", "yes", "no", "on", "off", "true", "false", "null"];
 
-    for line in lines.iter_mut() {
-        if config.rules.quotes.prefer_double {
-            // Заменяем одинарные кавычки на двойные
-            *line = line.replace('\'', "\"");
-        } else {
-            // Для простых строк удаляем кавычки
-            if let Some(caps) = re.captures(line) {
-                if let Some(matched) = caps.get(1).or(caps.get(2)) {
-                    let content = matched.as_str();
-                    // Убираем кавычки только если это простая строка
-                    if !content.contains(':') && !content.contains('#') && !content.is_empty() {
-                        *line = line.replace(&format!("\"{}\"", content), content)
-                                   .replace(&format!("'{}'", content), content);
-                    }
+    let content = lines.join("\n");
+    let scanned = scanner::scan(&content);
+    let mut fixed = 0;
+
+    for line in &scanned {
+        let mut replacements = vec![];
+
+        for token in &line.tokens {
+            let (TokenKind::Key(style) | TokenKind::Scalar(style)) = token.kind else { continue };
+            let quote = match style {
+                ScalarStyle::SingleQuoted => '\'',
+                ScalarStyle::DoubleQuoted => '"',
+                ScalarStyle::Plain => continue,
+            };
+
+            // Многострочные скаляры в кавычках не трогаем
+            let text = token.text;
+            if text.len() < 2 || !text.starts_with(quote) || !text.ends_with(quote) {
+                continue;
+            }
+            let inner = &text[1..text.len() - 1];
+
+            let replacement = if config.rules.quotes.prefer_double {
+                if quote == '"' {
+                    continue;
+                }
+                let value = inner.replace("''", "'");
+                format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                if !plain_safe.is_match(inner) || reserved.contains(&inner.to_lowercase().as_str()) {
+                    continue;
+                }
+                inner.to_string()
+            };
+
+            replacements.push((token.offset, text.len(), replacement));
+        }
+
+        if replacements.is_empty() {
+            continue;
+        }
+
+        // С конца строки, чтобы смещения оставшихся токенов не сдвигались
+        let target = &mut lines[line.number - 1];
+        for (offset, len, replacement) in replacements.into_iter().rev() {
+            target.replace_range(offset..offset + len, &replacement);
+        }
+        fixed += 1;
+    }
+
+    fixed
+}
+
+/// Лишние пробелы сокращаются до допустимого числа. После `:` остаётся
+/// хотя бы один пробел: без него `key:value` станет простой строкой
+fn fix_colons(lines: &mut [String], config: &Config) -> usize {
+    let rule = &config.rules.colons;
+
+    if rule.level == Severity::Off {
+        return 0;
+    }
+
+    let content = lines.join("\n");
+    let scanned = scanner::scan(&content);
+    let mut fixed = 0;
+
+    for line in &scanned {
+        let mut replacements = vec![];
+
+        for gap in line.colon_gaps() {
+            if gap.before.len() > rule.max_spaces_before {
+                replacements.push((gap.before.clone(), " ".repeat(rule.max_spaces_before)));
+            }
+            if let Some(after) = gap.after.filter(|a| a.len() > rule.max_spaces_after.max(1)) {
+                replacements.push((after, " ".repeat(rule.max_spaces_after.max(1))));
+            }
+        }
+
+        if replacements.is_empty() {
+            continue;
+        }
+
+        let target = &mut lines[line.number - 1];
+        for (range, replacement) in replacements.into_iter().rev() {
+            target.replace_range(range, &replacement);
+        }
+        fixed += 1;
+    }
+
+    fixed
+}
+
+/// Элементы, начинающиеся с ключа или вложенного `-`, не трогаются: по их
+/// колонке выровнены следующие строки элемента, и сдвиг сломал бы структуру
+fn fix_hyphens(lines: &mut [String], config: &Config) -> usize {
+    let rule = &config.rules.hyphens;
+
+    if rule.level == Severity::Off {
+        return 0;
+    }
+
+    let content = lines.join("\n");
+    let scanned = scanner::scan(&content);
+    let mut fixed = 0;
+
+    for line in &scanned {
+        let ranges: Vec<_> = line
+            .hyphen_gaps()
+            .into_iter()
+            .filter(|gap| !gap.starts_block && gap.after.len() > rule.max_spaces_after.max(1))
+            .map(|gap| gap.after)
+            .collect();
+
+        if ranges.is_empty() {
+            continue;
+        }
+
+        let target = &mut lines[line.number - 1];
+        for range in ranges.into_iter().rev() {
+            target.replace_range(range, " ");
+        }
+        fixed += 1;
+    }
+
+    fixed
+}
+
+fn fix_commas(lines: &mut [String], config: &Config) -> usize {
+    let rule = &config.rules.commas;
+
+    if rule.level == Severity::Off {
+        return 0;
+    }
+
+    let content = lines.join("\n");
+    let scanned = scanner::scan(&content);
+    let mut fixed = 0;
+
+    for line in &scanned {
+        let mut replacements = vec![];
+
+        for gap in line.comma_gaps() {
+            if let Some(before) = gap.before.filter(|b| b.len() > rule.max_spaces_before) {
+                replacements.push((before, rule.max_spaces_before));
+            }
+            if let Some(after) = gap.after {
+                let spaces = after.len().clamp(rule.min_spaces_after, rule.max_spaces_after.max(rule.min_spaces_after));
+                if spaces != after.len() {
+                    replacements.push((after, spaces));
                 }
             }
         }
+
+        if replacements.is_empty() {
+            continue;
+        }
+
+        let target = &mut lines[line.number - 1];
+        for (range, spaces) in replacements.into_iter().rev() {
+            target.replace_range(range, &" ".repeat(spaces));
+        }
+        fixed += 1;
     }
+
+    fixed
 }
 
-pub fn format_files<P: AsRef<Path>>(path: P, in_place: bool, config: &Config) -> anyhow::Result<()> {
-    use ignore::Walk;
+/// Форматирует файлы каталога; с `diff` печатает изменения, не записывая их.
+/// Возвращает пути файлов, которые изменились (или изменились бы), в порядке вывода
+pub fn format_files<P: AsRef<Path>>(path: P, in_place: bool, diff: bool, jobs: usize, print_fixed: bool,
+                                    config: &Config) -> anyhow::Result<Vec<PathBuf>> {
+    let root = path.as_ref();
+    let mut files = vec![];
 
-    for entry in Walk::new(path) {
-        let entry = entry?;
+    for entry in config::walk(root) {
+        let entry = entry.with_context(|| format!("failed to walk directory {}", root.display()))?;
         let path = entry.path();
 
-        if path.is_file() && path.extension().map_or(false, |ext| ext == "yaml" || ext == "yml") {
-            let content = fs::read_to_string(path)?;
-            let formatted = fix_content(&content, config);
+        if path.is_file() && config.is_yaml_file(path) {
+            files.push(path.to_path_buf());
+        }
+    }
+
+    // Сортировка даёт детерминированный порядок вывода при любом числе потоков
+    files.sort();
+
+    let jobs = match jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
 
-            if content != formatted {
-                if in_place {
-                    if config.format.backup_files {
-                        let backup_path = path.with_extension("yaml.bak");
-                        fs::copy(path, backup_path)?;
+    // Каждый файл обрабатывается ровно одним потоком, а резервная копия
+    // у каждого файла своя, поэтому записи не пересекаются
+    let next = AtomicUsize::new(0);
+    let mut outcomes: Vec<(usize, anyhow::Result<Option<Changed>>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(files.len()).max(1))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(path) = files.get(index) else { break };
+                        done.push((index, format_file(path, in_place, config)));
                     }
-                    fs::write(path, formatted)?;
-                    println!("Formatted: {}", path.display());
-                } else {
-                    println!("// File: {}", path.display());
-                    println!("{}", formatted);
-                    println!("---");
-                }
+                    done
+                })
+            })
+            .collect();
+
+        workers.into_iter().flat_map(|w| w.join().expect("format worker panicked")).collect()
+    });
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    let mut changed = vec![];
+
+    for (index, outcome) in outcomes {
+        let path = &files[index];
+
+        if let Some((original, formatted)) = outcome? {
+            changed.push(path.clone());
+
+            if diff {
+                print!("{}", unified_diff(&original, &formatted, &path.to_string_lossy()));
+            } else if in_place {
+                report_modified("Formatted", path, print_fixed);
+            } else {
+                println!("// File: {}", path.display());
+                println!("{}", formatted);
+                println!("---");
             }
         }
     }
 
-    Ok(())
+    Ok(changed)
+}
+
+/// Форматирует один файл; если содержимое изменилось, возвращает исходное и новое
+fn format_file(path: &Path, in_place: bool, config: &Config) -> anyhow::Result<Option<Changed>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let formatted = format_content(&content, &path.to_string_lossy(), config);
+
+    if content == formatted {
+        return Ok(None);
+    }
+
+    if in_place {
+        if config.format.backup_files {
+            let backup_path = backup_path(path);
+            fs::copy(path, &backup_path)
+                .with_context(|| format!("failed to write backup {}", backup_path.display()))?;
+        }
+        fs::write(path, &formatted)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    Ok(Some((content, formatted)))
+}
+
+/// Сообщает об изменённом файле; с `--print-fixed` — только путь,
+/// чтобы вывод можно было передать, например, в `git add`
+fn report_modified(action: &str, path: &Path, print_fixed: bool) {
+    if print_fixed {
+        println!("{}", path.display());
+    } else {
+        println!("{}: {}", action, path.display());
+    }
+}
+
+/// `a.yml` -> `a.yml.bak`: у `a.yaml` и `a.yml` разные резервные копии
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linter::YamlLinter;
+
+    #[test]
+    fn only_modified_files_are_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        let clean = dir.path().join("clean.yaml");
+        let dirty = dir.path().join("dirty.yaml");
+        let unfixable = dir.path().join("unfixable.yaml");
+        fs::write(&clean, "---\nkey: value\n").unwrap();
+        fs::write(&dirty, "---\nkey: value   \n").unwrap();
+        fs::write(&unfixable, "---\nkey: value\nkey: other\n").unwrap();
+
+        let linter = YamlLinter::new(Config::default()).unwrap();
+        let reports: Vec<_> = [&clean, &dirty, &unfixable]
+            .iter()
+            .map(|path| linter.lint_file(path).unwrap())
+            .collect();
+        assert!(!reports[2].results.is_empty());
+
+        let fixed = auto_fix_files(&reports, &linter.config, false).unwrap();
+        let dirty = dirty.to_string_lossy().to_string();
+
+        assert_eq!(fixed.modified, vec![dirty.clone()]);
+        assert_eq!(modified_list(&fixed.modified, false), format!("{}\n", dirty));
+        assert_eq!(modified_list(&fixed.modified, true), serde_json::to_string(&[dirty]).unwrap());
+    }
+
+    #[test]
+    fn fix_counts_changed_lines_per_rule() {
+        let outcome = fix_content("---\nkey: value  \nmap:\n   a: 1 \n   b: 2\n\n\n\n", &Config::default());
+
+        assert!(outcome.rejected.is_none());
+        assert_eq!(outcome.content, "---\nkey: value\nmap:\n  a: 1\n  b: 2\n");
+        assert_eq!(outcome.fixes["trailing-spaces"], 2);
+        assert_eq!(outcome.fixes["indentation"], 2);
+        assert_eq!(outcome.fixes["empty-lines"], 3);
+        assert_eq!(outcome.fixes["quotes"], 0);
+        assert_eq!(
+            fix_summary(&outcome.fixes).unwrap(),
+            "Fix summary: empty-lines: 3 line(s) changed, indentation: 2 line(s) changed, \
+             trailing-spaces: 2 line(s) changed"
+        );
+    }
+
+    #[test]
+    fn format_files_formats_a_directory_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = ["e.yaml", "a.yaml", "d.yaml", "c.yaml", "b.yaml"];
+        for name in names {
+            fs::write(dir.path().join(name), "---\nkey: value   \n").unwrap();
+        }
+        fs::write(dir.path().join("clean.yaml"), "---\nkey: value\n").unwrap();
+
+        let changed = format_files(dir.path(), true, false, 4, true, &Config::default()).unwrap();
+
+        let mut expected: Vec<PathBuf> = names.iter().map(|name| dir.path().join(name)).collect();
+        expected.sort();
+        assert_eq!(changed, expected);
+        for path in &expected {
+            assert_eq!(fs::read_to_string(path).unwrap(), "---\nkey: value\n");
+        }
+    }
+
+    #[test]
+    fn broken_files_are_skipped_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let broken = dir.path().join("broken.yaml");
+        let content = "---\nkey: [a, b   \nother: 1  \n";
+        fs::write(&broken, content).unwrap();
+
+        let linter = YamlLinter::new(Config::default()).unwrap();
+        let reports = vec![linter.lint_file(&broken).unwrap()];
+        assert!(reports[0].results.iter().any(|r| r.rule == "syntax"));
+
+        let fixed = auto_fix_files(&reports, &linter.config, false).unwrap();
+        assert!(fixed.modified.is_empty());
+        assert_eq!(fs::read_to_string(&broken).unwrap(), content);
+    }
+
+    #[test]
+    fn block_scalars_survive_formatting() {
+        let script = "script: |\n    echo  one,two   # not a comment\n      - not: a list\n    done\n";
+        let folded = "note: >-\n   first:line\n   second\n";
+        let content = format!("---\nkey:   value\n{}{}", script, folded);
+
+        let outcome = fix_content(&content, &Config::default());
+
+        assert!(outcome.rejected.is_none(), "{:?}", outcome.rejected);
+        // Содержимое блоков не трогается; сдвиг всего блока значение не меняет
+        assert_eq!(outcome.content, format!("---\nkey: value\n{}note: >-\n  first:line\n  second\n", script));
+        let parse = |text: &str| serde_yaml::from_str::<Value>(text).unwrap();
+        assert_eq!(parse(&outcome.content), parse(&content));
+    }
+
+    #[test]
+    fn diff_skips_unreadable_and_broken_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dirty = dir.path().join("dirty.yaml");
+        let broken = dir.path().join("broken.yaml");
+        fs::write(&dirty, "---\nkey: value   \n").unwrap();
+        fs::write(&broken, "---\nkey: [a   \n").unwrap();
+        fs::write(dir.path().join("README.md"), "# readme\n").unwrap();
+
+        let list: Vec<String> = [dir.path().join("missing.yaml"), dir.path().join("README.md"), dirty.clone(), broken]
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        let linter = YamlLinter::new(Config::default()).unwrap();
+        let reports = linter.lint_file_list(&list);
+
+        let patches = diff_reports(&reports, &linter.config);
+        assert_eq!(patches.len(), 1, "{:?}", patches);
+        assert!(patches[0].contains("dirty.yaml") && patches[0].contains("+key: value\n"), "{}", patches[0]);
+    }
+
+    #[test]
+    fn empty_file_stays_empty() {
+        let linter = YamlLinter::new(Config::default()).unwrap();
+        let report = linter.lint_reader("".as_bytes(), "empty.yaml").unwrap();
+        assert!(report.results.is_empty(), "{:?}", report.results);
+
+        let outcome = fix_content("", &linter.config);
+        assert_eq!(outcome.content, "");
+        assert!(outcome.fixes.values().all(|count| *count == 0));
+        assert!(diff_reports(&[report], &linter.config).is_empty());
+    }
+
+    /// Прогоняет fix_quotes и проверяет, что значения после разбора не изменились
+    fn requote(content: &str, prefer_double: bool) -> String {
+        let mut config = Config::default();
+        config.rules.quotes.prefer_double = prefer_double;
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        fix_quotes(&mut lines, &config);
+        let fixed = lines.join("\n") + "\n";
+
+        let before: serde_yaml::Value = serde_yaml::from_str(content).unwrap();
+        let after: serde_yaml::Value = serde_yaml::from_str(&fixed)
+            .unwrap_or_else(|e| panic!("fixed content does not parse: {}\n{}", e, fixed));
+        assert_eq!(before, after, "{}", fixed);
+        fixed
+    }
+
+    #[test]
+    fn fix_quotes_keeps_apostrophes() {
+        let content = "---\nmsg: \"it's fine\"\nother: 'it''s fine'\n";
+
+        assert_eq!(requote(content, false), content);
+        assert_eq!(requote(content, true), "---\nmsg: \"it's fine\"\nother: \"it's fine\"\n");
+    }
+
+    #[test]
+    fn fix_quotes_keeps_urls_quoted() {
+        let content = "---\nurl: \"http://x\"\nmirror: 'http://y'\n";
+
+        assert_eq!(requote(content, false), content);
+        assert_eq!(requote(content, true), "---\nurl: \"http://x\"\nmirror: \"http://y\"\n");
+    }
+
+    #[test]
+    fn fix_quotes_keeps_hashes_inside_quotes() {
+        let content = "---\ncolor: \"#ff0000\"\nnote: 'a # b' # comment\n";
+
+        assert_eq!(requote(content, false), content);
+        assert_eq!(requote(content, true), "---\ncolor: \"#ff0000\"\nnote: \"a # b\" # comment\n");
+    }
 }
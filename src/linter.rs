@@ -1,70 +1,285 @@
-use crate::config::Config;
-use crate::rules::{RuleChecker, LintResult};
-use ignore::Walk;
+use crate::archive;
+use crate::config::{self, Config, Severity};
+use crate::rules::{self, RuleChecker, LintResult};
+use crate::scanner;
+use crate::schema;
+use crate::template::{self, TemplateMode};
+use anyhow::Context;
+use globset::GlobSet;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::io::Read;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+/// Путь, означающий стандартный ввод
+pub const STDIN_PATH: &str = "-";
+
+/// Имя стандартного ввода в отчётах
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// Правило находок для путей из `--files-from`, которые нельзя проверить
+pub const FILE_LIST_RULE: &str = "files-from";
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LintReport {
     pub file: String,
     pub results: Vec<LintResult>,
     pub passed: bool,
+    /// Исходный текст, по которому считаются смещения в подробном выводе
+    #[serde(skip)]
+    pub source: String,
 }
 
 pub struct YamlLinter {
     pub config: Config,
     checker: RuleChecker,
+    exclude: GlobSet,
 }
 
 impl YamlLinter {
-    pub fn new(config: Config) -> Self {
-        let checker = RuleChecker::new(config.clone());
-        YamlLinter { config, checker }
+    pub fn new(config: Config) -> anyhow::Result<Self> {
+        let checker = RuleChecker::new(config.clone())?;
+        let exclude = config.exclude_set()?;
+        Ok(YamlLinter { config, checker, exclude })
+    }
+
+    /// Путь сопоставляется с `exclude` относительно корня обхода
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude.is_match(path)
+    }
+
+    /// Файл из-под `root` проверяется при обходе: YAML и не исключён
+    pub fn should_lint(&self, root: &Path, path: &Path) -> bool {
+        path.is_file() && self.config.is_yaml_file(path) && !self.is_excluded(path.strip_prefix(root).unwrap_or(path))
     }
 
     pub fn lint_file<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<LintReport> {
         let path = path.as_ref();
-        let content = fs::read_to_string(path)?;
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        Ok(self.lint_content(content, &path.to_string_lossy()))
+    }
+
+    /// Проверяет файл вместе с подключёнными через `includes.tags` файлами
+    pub fn lint_file_with_includes<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<Vec<LintReport>> {
+        let mut reports = vec![];
+        self.lint_included(path.as_ref(), &mut HashSet::new(), &mut reports)?;
+        Ok(reports)
+    }
+
+    /// `visited` защищает от циклов и повторной проверки файла,
+    /// подключённого из нескольких мест
+    fn lint_included(&self, path: &Path, visited: &mut HashSet<PathBuf>,
+                     reports: &mut Vec<LintReport>) -> anyhow::Result<()> {
+        let key = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if !visited.insert(key) {
+            return Ok(());
+        }
+
+        let mut report = self.lint_file(path)?;
+        let mut children = vec![];
+
+        if self.config.includes.enabled {
+            let lines = scanner::scan(&report.source);
+            let base = path.parent().unwrap_or(Path::new(""));
+
+            for (line, token) in scanner::tagged_scalars(&lines, &self.config.includes.tags) {
+                let child = base.join(token.unquoted());
+
+                if child.is_file() {
+                    children.push(child);
+                    continue;
+                }
+
+                report.passed = false;
+                report.results.push(LintResult {
+                    file: report.file.clone(),
+                    line: line.number,
+                    column: token.column,
+                    length: token.text.chars().count(),
+                    severity: Severity::Error,
+                    rule: "include".to_string(),
+                    message: format!("Included file not found: {}", child.display()),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        reports.push(report);
+
+        for child in children {
+            self.lint_included(&child, visited, reports)?;
+        }
+
+        Ok(())
+    }
+
+    /// Проверяет документ из потока (например, стандартного ввода)
+    pub fn lint_reader<R: Read>(&self, mut reader: R, name: &str) -> anyhow::Result<LintReport> {
+        let mut content = String::new();
+        reader
+            .read_to_string(&mut content)
+            .with_context(|| format!("failed to read {}", name))?;
+
+        Ok(self.lint_content(content, name))
+    }
 
-        let results = self.checker.check_file(&content, path.to_str().unwrap_or(""));
+    fn lint_content(&self, content: String, name: &str) -> LintReport {
+        let results = match self.config.template {
+            Some(TemplateMode::Helm) => self.lint_helm_template(&content, name),
+            None => self.checker.check_file(&content, name),
+        };
 
-        Ok(LintReport {
-            file: path.to_string_lossy().to_string(),
-            results: results.clone(),
+        LintReport {
+            file: name.to_string(),
             passed: !results.iter().any(|r| r.is_error()),
-        })
+            results,
+            source: content,
+        }
+    }
+
+    /// Позиции в замаскированном тексте совпадают с исходными, поэтому
+    /// достаточно вернуть находкам исходный текст строки
+    fn lint_helm_template(&self, content: &str, name: &str) -> Vec<LintResult> {
+        let masked = template::mask_helm(content);
+        let lines: Vec<&str> = content.lines().collect();
+
+        let mut results = self.checker.check_file(&masked, name);
+        results.retain(|r| !template::HELM_SKIPPED_RULES.contains(&r.rule.as_str()));
+
+        for result in &mut results {
+            if let Some(line) = lines.get(result.line.wrapping_sub(1)) {
+                result.snippet = line.to_string();
+            }
+        }
+
+        results
     }
 
     pub fn lint_directory<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<Vec<LintReport>> {
+        let root = path.as_ref();
         let mut reports = vec![];
+        let mut visited = HashSet::new();
 
-        for entry in Walk::new(path) {
-            let entry = entry?;
+        for entry in config::walk(root) {
+            let entry = entry.with_context(|| format!("failed to walk directory {}", root.display()))?;
             let path = entry.path();
 
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "yaml" || ext == "yml") {
+            if path.is_file() && self.config.is_yaml_file(path) {
                 let path_str = path.to_string_lossy().to_string();
 
-                if self.config.should_exclude(&path_str) {
+                if self.is_excluded(path.strip_prefix(root).unwrap_or(path)) {
                     continue;
                 }
 
-                match self.lint_file(path) {
-                    Ok(report) => reports.push(report),
-                    Err(e) => eprintln!("Error processing {}: {}", path_str, e),
+                if let Err(e) = self.lint_included(path, &mut visited, &mut reports) {
+                    eprintln!("Error processing {}: {:#}", path_str, e);
                 }
             }
         }
 
+        self.check_directory_consistency(&mut reports);
+
+        Ok(reports)
+    }
+
+    /// Проверяет ровно перечисленные файлы, без обхода директорий и `exclude`.
+    /// Отсутствующий или не YAML-файл становится ошибкой в его отчёте,
+    /// а не прерывает проверку остальных
+    pub fn lint_file_list(&self, paths: &[String]) -> Vec<LintReport> {
+        let mut reports = vec![];
+        let mut visited = HashSet::new();
+
+        for path in paths {
+            let problem = if !Path::new(path).is_file() {
+                Some("File not found")
+            } else if !self.config.is_yaml_file(path) {
+                Some("Not a YAML file (extension is not in `extensions`)")
+            } else {
+                None
+            };
+
+            if let Some(message) = problem {
+                reports.push(LintReport {
+                    file: path.clone(),
+                    results: vec![LintResult {
+                        file: path.clone(),
+                        line: 1,
+                        column: 1,
+                        length: 0,
+                        severity: Severity::Error,
+                        rule: FILE_LIST_RULE.to_string(),
+                        message: message.to_string(),
+                        snippet: "".to_string(),
+                    }],
+                    passed: false,
+                    source: String::new(),
+                });
+                continue;
+            }
+
+            if let Err(e) = self.lint_included(Path::new(path), &mut visited, &mut reports) {
+                eprintln!("Error processing {}: {:#}", path, e);
+            }
+        }
+
+        reports
+    }
+
+    /// Межфайловые правила добавляют нарушения к отчётам уже проверенных файлов
+    fn check_directory_consistency(&self, reports: &mut [LintReport]) {
+        let files: Vec<_> = reports.iter().map(|r| (r.file.as_str(), r.source.as_str())).collect();
+        let results = self.checker.check_document_start_consistency(&files);
+
+        for result in results {
+            if let Some(report) = reports.iter_mut().find(|r| r.file == result.file) {
+                report.passed &= !result.is_error();
+                report.results.push(result);
+            }
+        }
+    }
+
+    /// Проверяет YAML-файлы внутри zip/tar архива без распаковки.
+    /// Пути в отчётах имеют вид `archive.zip!dir/file.yaml`.
+    pub fn lint_archive<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<Vec<LintReport>> {
+        let path = path.as_ref();
+        let mut reports = vec![];
+
+        let entries = archive::read_yaml_entries(path, &self.config)
+            .with_context(|| format!("failed to read archive {}", path.display()))?;
+
+        for entry in entries {
+            let entry_path = format!("{}!{}", path.display(), entry.name);
+
+            if self.is_excluded(Path::new(&entry.name)) {
+                continue;
+            }
+
+            match entry.content {
+                Ok(content) => reports.push(self.lint_content(content, &entry_path)),
+                Err(e) => eprintln!("Error processing {}: {:#}", entry_path, e),
+            }
+        }
+
         Ok(reports)
     }
 
-    pub fn print_results(&self, reports: &[LintReport]) {
+    pub fn print_results(&self, reports: &[LintReport], verbose: bool) {
         use colored::*;
 
         let mut total_errors = 0;
         let mut total_warnings = 0;
 
+        if verbose {
+            match &self.config.source {
+                Some(path) => println!("Config: {}", path.display()),
+                None => println!("Config: built-in defaults"),
+            }
+            println!("Processing {} file(s)", reports.len());
+        }
+
         for report in reports {
             if report.results.is_empty() {
                 println!("{} {}: {}", "✓".green(), report.file, "OK".green());
@@ -90,7 +305,13 @@ impl YamlLinter {
                 );
 
                 if !result.snippet.is_empty() {
-                    println!("      {}", result.snippet.dimmed());
+                    println!("      {}", highlight_span(&result.snippet, result.column, result.length, color));
+                }
+
+                if verbose {
+                    for detail in verbose_details(result, &report.source) {
+                        println!("      {}", detail);
+                    }
                 }
 
                 match result.severity {
@@ -112,7 +333,25 @@ impl YamlLinter {
         }
     }
 
-    pub fn validate_file<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<ValidationResult> {
+    /// Со схемой проверяет документ по JSON Schema, без неё — правилами линтера
+    pub fn validate_file<P: AsRef<Path>>(&self, path: P, schema: Option<&str>) -> anyhow::Result<ValidationResult> {
+        let path = path.as_ref();
+
+        if let Some(schema_path) = schema {
+            let schema = schema::load(schema_path)?;
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+
+            let errors = schema::validate(&schema, &content)?;
+
+            return Ok(ValidationResult {
+                file: path.to_string_lossy().to_string(),
+                valid: errors.is_empty(),
+                errors,
+                warnings: vec![],
+            });
+        }
+
         let report = self.lint_file(path)?;
 
         Ok(ValidationResult {
@@ -156,6 +395,41 @@ impl YamlLinter {
     }
 }
 
+/// Фрагмент строки с нарушением выделяется цветом правила, остальное приглушено
+/// Подробности находки для `--verbose`: описание правила, смещение в байтах и совет
+fn verbose_details(result: &LintResult, source: &str) -> Vec<String> {
+    let info = rules::rule_info(&result.rule);
+    let mut details = vec![];
+
+    if !info.description.is_empty() {
+        details.push(format!("rule: {}", info.description));
+    }
+    details.push(format!("offset: {}", rules::byte_offset(source, result.line, result.column)));
+    if !info.suggestion.is_empty() {
+        details.push(format!("fix: {}", info.suggestion));
+    }
+
+    details
+}
+
+fn highlight_span(snippet: &str, column: usize, length: usize, color: colored::Color) -> String {
+    use colored::*;
+
+    let chars: Vec<char> = snippet.chars().collect();
+    let start = column.saturating_sub(1).min(chars.len());
+    let end = (start + length).min(chars.len());
+
+    if start == end {
+        return snippet.dimmed().to_string();
+    }
+
+    let before: String = chars[..start].iter().collect();
+    let span: String = chars[start..end].iter().collect();
+    let after: String = chars[end..].iter().collect();
+
+    format!("{}{}{}", before.dimmed(), span.color(color).underline(), after.dimmed())
+}
+
 #[derive(Debug)]
 pub struct ValidationResult {
     pub file: String,
@@ -163,3 +437,150 @@ pub struct ValidationResult {
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn included_file_is_linted_with_its_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let parent = dir.path().join("main.yaml");
+        let child = dir.path().join("parts/child.yaml");
+        fs::create_dir(dir.path().join("parts")).unwrap();
+        fs::write(&parent, "---\nchild: !include parts/child.yaml\nmissing: !include parts/none.yaml\n").unwrap();
+        fs::write(&child, "---\nkey: value\nkey: again\n").unwrap();
+
+        let mut config = Config::default();
+        config.includes.enabled = true;
+        let reports = YamlLinter::new(config).unwrap().lint_file_with_includes(&parent).unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].results.iter().map(|r| (r.rule.as_str(), r.line)).collect::<Vec<_>>(),
+                   vec![("include", 3)]);
+        assert_eq!(reports[1].file, child.to_string_lossy());
+        assert!(!reports[1].passed);
+        assert!(reports[1].results.iter().any(|r| r.rule == "duplicates"), "{:?}", reports[1].results);
+    }
+
+    #[test]
+    fn document_start_consistency_flags_the_odd_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.yaml"), "---\na: 1\n").unwrap();
+        fs::write(dir.path().join("b.yaml"), "---\nb: 1\n").unwrap();
+        fs::write(dir.path().join("c.yaml"), "c: 1\n").unwrap();
+
+        let mut config = Config::default();
+        config.rules.document_start_consistency.level = Severity::Warning;
+        let reports = YamlLinter::new(config).unwrap().lint_directory(dir.path()).unwrap();
+
+        let flagged: Vec<&str> = reports
+            .iter()
+            .filter(|r| r.results.iter().any(|result| result.rule == "document-start-consistency"))
+            .map(|r| r.file.as_str())
+            .collect();
+        assert_eq!(flagged.len(), 1);
+        assert!(flagged[0].ends_with("c.yaml"), "{:?}", flagged);
+    }
+
+    #[test]
+    fn highlight_span_colors_only_the_span() {
+        use colored::*;
+        colored::control::set_override(true);
+
+        let highlighted = highlight_span("port: 70000", 7, 5, Color::Red);
+
+        assert_eq!(highlighted, format!("{}{}{}", "port: ".dimmed(), "70000".red().underline(), "".dimmed()));
+        assert!(highlighted.contains("\x1b["), "{:?}", highlighted);
+        // Без длины выделять нечего: строка только приглушается
+        assert_eq!(highlight_span("port: 70000", 7, 0, Color::Red), "port: 70000".dimmed().to_string());
+    }
+
+    #[test]
+    fn archive_entries_are_reported_inside_the_archive() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in [
+            ("good.yaml", "---\nkey: value\n"),
+            ("conf/bad.yaml", "---\nkey: value\nkey: again\n"),
+            ("README.md", "# not yaml\n"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let reports = YamlLinter::new(Config::default()).unwrap().lint_archive(&path).unwrap();
+
+        let state: Vec<(String, bool)> = reports.iter().map(|r| (r.file.clone(), r.passed)).collect();
+        let archive = path.display();
+        assert_eq!(
+            state,
+            vec![(format!("{}!good.yaml", archive), true), (format!("{}!conf/bad.yaml", archive), false)]
+        );
+        assert!(reports[1].results.iter().all(|r| r.file == reports[1].file));
+    }
+
+    #[test]
+    fn verbose_details_describe_the_rule() {
+        let linter = YamlLinter::new(Config::default()).unwrap();
+        let report = linter.lint_content("---\nkey: value\nkey: again\n".to_string(), "dup.yaml");
+        let result = report.results.iter().find(|r| r.rule == "duplicates").unwrap();
+
+        let details = verbose_details(result, &report.source);
+
+        assert_eq!(details[0], format!("rule: {}", rules::rule_info("duplicates").description));
+        assert_eq!(details[1], "offset: 15");
+        assert!(details[2].starts_with("fix: "), "{:?}", details);
+    }
+
+    #[test]
+    fn excluded_templates_and_test_directories_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["app.yaml", "app.tpl.yaml", "test/a.yaml", "src/test/b.yaml", "src/main.yaml"] {
+            let path = dir.path().join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "---\nkey: value\n").unwrap();
+        }
+
+        let config = Config {
+            exclude: vec!["*.tpl.yaml".to_string(), "**/test/**".to_string()],
+            ..Config::default()
+        };
+        let reports = YamlLinter::new(config).unwrap().lint_directory(dir.path()).unwrap();
+
+        let mut linted: Vec<String> = reports
+            .iter()
+            .map(|r| Path::new(&r.file).strip_prefix(dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        linted.sort();
+        assert_eq!(linted, vec!["app.yaml", "src/main.yaml"]);
+    }
+
+    #[test]
+    fn lint_file_errors_name_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let linter = YamlLinter::new(Config::default()).unwrap();
+
+        let missing = dir.path().join("missing.yaml");
+        let error = linter.lint_file(&missing).unwrap_err();
+        assert!(format!("{:#}", error).contains(&missing.display().to_string()), "{:#}", error);
+
+        let binary = dir.path().join("binary.yaml");
+        fs::write(&binary, [0xff, 0xfe, b'\n']).unwrap();
+        let error = linter.lint_file(&binary).unwrap_err();
+        assert!(format!("{:#}", error).contains(&binary.display().to_string()), "{:#}", error);
+
+        let broken = dir.path().join("broken.yaml");
+        fs::write(&broken, "---\nkey: [a, b\n").unwrap();
+        let report = linter.lint_file(&broken).unwrap();
+        let syntax: Vec<&LintResult> = report.results.iter().filter(|r| r.rule == "syntax").collect();
+        assert_eq!(syntax.len(), 1, "{:?}", report.results);
+        assert_eq!(syntax[0].file, broken.display().to_string());
+        assert_eq!(report.file, broken.display().to_string());
+    }
+}
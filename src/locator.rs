@@ -0,0 +1,240 @@
+//! Сопоставление путей в дереве YAML с позициями в исходном тексте.
+//!
+//! `serde_yaml::Value` не хранит позиции, поэтому семантические правила
+//! находят место нарушения по пути к узлу (`metadata.name`, `items[2]`),
+//! а индекс строится по токенам лексера с учётом отступов и flow-коллекций.
+
+use crate::scanner::{Line, TokenKind};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Позиция в файле: строка и колонка в символах, обе с 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Узел дерева: позиция ключа (или индикатора `-`) и скалярного значения
+#[derive(Debug, Clone)]
+pub struct Node {
+    /// Номер документа в потоке, с 0
+    pub document: usize,
+    pub path: Vec<PathSegment>,
+    pub key: Position,
+    pub value: Option<Position>,
+}
+
+pub struct PathIndex {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    /// Колонка токена, открывшего уровень; для flow-коллекций не используется
+    column: usize,
+    segment: PathSegment,
+}
+
+struct FlowFrame {
+    is_mapping: bool,
+    /// Глубина стека при открытии коллекции
+    base: usize,
+    /// Позиция текущего элемента последовательности уже записана
+    recorded: bool,
+}
+
+impl PathIndex {
+    pub fn build(lines: &[Line]) -> Self {
+        let mut nodes = vec![];
+        let mut stack: Vec<Frame> = vec![];
+        let mut flows: Vec<FlowFrame> = vec![];
+        let mut document = 0;
+        // В текущем документе уже было содержимое или явный `---`
+        let mut started = false;
+
+        let path_of = |stack: &[Frame]| stack.iter().map(|f| f.segment.clone()).collect::<Vec<_>>();
+
+        for line in lines {
+            for token in &line.tokens {
+                let position = Position { line: line.number, column: token.column };
+
+                if !matches!(token.kind, TokenKind::DocumentStart | TokenKind::DocumentEnd | TokenKind::Comment) {
+                    started = true;
+                }
+
+                match token.kind {
+                    TokenKind::DocumentStart | TokenKind::DocumentEnd => {
+                        stack.clear();
+                        flows.clear();
+                        // Нумерация совпадает с документами serde_yaml: `---` в начале
+                        // файла и после `...` не открывает ещё один документ
+                        if started {
+                            document += 1;
+                        }
+                        started = token.kind == TokenKind::DocumentStart;
+                    }
+
+                    TokenKind::SequenceEntry if flows.is_empty() => {
+                        stack.retain(|f| f.column <= token.column);
+
+                        let mut index = 0;
+                        if let Some(top) = stack.last() {
+                            if let (PathSegment::Index(prev), true) = (&top.segment, top.column == token.column) {
+                                index = prev + 1;
+                                stack.pop();
+                            }
+                        }
+
+                        stack.push(Frame { column: token.column, segment: PathSegment::Index(index) });
+                        nodes.push(Node { document, path: path_of(&stack), key: position, value: None });
+                    }
+
+                    TokenKind::Key(_) if flows.is_empty() => {
+                        stack.retain(|f| f.column <= token.column);
+
+                        // Элемент последовательности на уровне ключа закончился
+                        if stack.last().is_some_and(|f| f.column == token.column && matches!(f.segment, PathSegment::Index(_))) {
+                            stack.pop();
+                        }
+                        // Соседний ключ того же маппинга
+                        if stack.last().is_some_and(|f| f.column == token.column) {
+                            stack.pop();
+                        }
+
+                        stack.push(Frame {
+                            column: token.column,
+                            segment: PathSegment::Key(token.unquoted().to_string()),
+                        });
+                        nodes.push(Node { document, path: path_of(&stack), key: position, value: None });
+                    }
+
+                    TokenKind::Key(_) => {
+                        let flow = flows.last_mut().expect("flow frame");
+                        stack.truncate(flow.base);
+                        stack.push(Frame {
+                            column: usize::MAX,
+                            segment: PathSegment::Key(token.unquoted().to_string()),
+                        });
+                        nodes.push(Node { document, path: path_of(&stack), key: position, value: None });
+                    }
+
+                    TokenKind::FlowStart | TokenKind::Scalar(_) | TokenKind::Alias | TokenKind::BlockScalar => {
+                        // Первое содержимое элемента flow-последовательности
+                        if let Some(flow) = flows.last_mut() {
+                            if !flow.is_mapping && !flow.recorded {
+                                flow.recorded = true;
+                                nodes.push(Node { document, path: path_of(&stack), key: position, value: None });
+                            }
+                        }
+
+                        if token.kind == TokenKind::FlowStart {
+                            let is_mapping = token.text == "{";
+                            flows.push(FlowFrame { is_mapping, base: stack.len(), recorded: false });
+                            if !is_mapping {
+                                stack.push(Frame { column: usize::MAX, segment: PathSegment::Index(0) });
+                            }
+                            continue;
+                        }
+
+                        // Скаляр — значение последнего открытого узла
+                        let path = path_of(&stack);
+                        if let Some(node) = nodes.iter_mut().rev().find(|n| n.document == document && n.path == path) {
+                            node.value.get_or_insert(position);
+                        }
+                    }
+
+                    TokenKind::Comma => {
+                        if let Some(flow) = flows.last_mut() {
+                            let next = match stack.get(flow.base) {
+                                Some(Frame { segment: PathSegment::Index(i), .. }) => i + 1,
+                                _ => 0,
+                            };
+                            stack.truncate(flow.base);
+                            if !flow.is_mapping {
+                                stack.push(Frame { column: usize::MAX, segment: PathSegment::Index(next) });
+                                flow.recorded = false;
+                            }
+                        }
+                    }
+
+                    TokenKind::FlowEnd => {
+                        if let Some(flow) = flows.pop() {
+                            stack.truncate(flow.base);
+                        }
+                    }
+
+                    _ => {}
+                }
+            }
+        }
+
+        PathIndex { nodes }
+    }
+
+    /// Повторные вхождения ключей внутри одного маппинга. `serde_yaml`
+    /// отвергает такие документы целиком, поэтому ищем их по токенам
+    pub fn duplicate_keys(&self) -> Vec<&Node> {
+        let mut seen = HashSet::new();
+
+        self.nodes
+            .iter()
+            .filter(|n| matches!(n.path.last(), Some(PathSegment::Key(_))))
+            .filter(|n| !seen.insert((n.document, &n.path)))
+            .collect()
+    }
+
+    /// Позиция первого узла документа
+    pub fn first_position(&self, document: usize) -> Option<Position> {
+        self.nodes.iter().find(|n| n.document == document).map(|n| n.key)
+    }
+
+    /// Первый узел документа с данным путём
+    pub fn node(&self, document: usize, path: &[PathSegment]) -> Option<&Node> {
+        self.nodes.iter().find(|n| n.document == document && n.path == path)
+    }
+
+    /// Позиция ключа узла; если узла нет — ближайшего найденного предка
+    pub fn key_position(&self, document: usize, path: &[PathSegment]) -> Option<Position> {
+        (0..=path.len()).rev().find_map(|len| self.node(document, &path[..len]).map(|n| n.key))
+    }
+
+    /// Позиция значения узла, а если оно не на строке ключа — позиция ключа
+    pub fn value_position(&self, document: usize, path: &[PathSegment]) -> Option<Position> {
+        match self.node(document, path) {
+            Some(node) => Some(node.value.unwrap_or(node.key)),
+            None => self.key_position(document, path),
+        }
+    }
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) => write!(f, "{}", key),
+            PathSegment::Index(i) => write!(f, "[{}]", i),
+        }
+    }
+}
+
+/// Путь в виде `metadata.labels.app` или `items[0].name`
+pub fn format_path(path: &[PathSegment]) -> String {
+    let mut result = String::new();
+
+    for segment in path {
+        if let PathSegment::Key(_) = segment {
+            if !result.is_empty() {
+                result.push('.');
+            }
+        }
+        result.push_str(&segment.to_string());
+    }
+
+    result
+}
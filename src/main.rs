@@ -1,47 +1,171 @@
+mod archive;
 mod cli;
 mod config;
+mod directives;
+mod exporter;
 mod linter;
+mod locator;
 mod rules;
 mod formatter;
+mod scanner;
+mod schema;
+mod template;
+mod watch;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use std::io::Read;
 use config::Config;
 use linter::YamlLinter;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 fn main() -> Result<()> {
     let cli = cli::Cli::parse();
 
-    // Загружаем конфигурацию
-    let config = match cli.config_path.as_ref() {
-        Some(path) => Config::from_file(path)?,
-        None => Config::default(),
+    // Загружаем конфигурацию: явно заданную, найденную от проверяемого пути
+    // вверх по каталогам или встроенную
+    let start = match cli.command.target() {
+        Some(path) if path != linter::STDIN_PATH => Path::new(path),
+        _ => Path::new("."),
     };
+    let config_paths: Vec<PathBuf> = if !cli.config_path.is_empty() {
+        cli.config_path.iter().map(PathBuf::from).collect()
+    } else if cli.no_config {
+        vec![]
+    } else {
+        Config::discover(start).into_iter().collect()
+    };
+    let mut config = if config_paths.is_empty() {
+        Config::default()
+    } else {
+        Config::from_files(&config_paths)?
+    };
+
+    if let cli::Commands::Check { template: Some(mode), .. } = &cli.command {
+        config.template = Some(*mode);
+    }
 
-    let linter = YamlLinter::new(config);
+    let linter = YamlLinter::new(config)?;
 
     match cli.command {
-        cli::Commands::Check { path, fix, quiet: _ } => {
-            let results = if Path::new(&path).is_dir() {
+        cli::Commands::Check { path, files_from, fix, quiet: _, print_fixed, fix_broken, output_format, output_file, exit_json, diff, template: _, watch } => {
+            let path = path.unwrap_or_default();
+            let is_archive = archive::is_archive(&path);
+            let is_stdin = path == linter::STDIN_PATH;
+
+            if fix && is_stdin {
+                anyhow::bail!("Cannot fix standard input, use `format -` instead");
+            }
+
+            if fix && is_archive {
+                anyhow::bail!("Cannot fix files inside an archive: {}", path);
+            }
+
+            if (fix || diff) && linter.config.template.is_some() {
+                anyhow::bail!("Cannot fix templates, fix the rendered output instead");
+            }
+
+            if watch && (is_stdin || is_archive) {
+                anyhow::bail!("Cannot watch {}, pass a file or directory", path);
+            }
+
+            let results = if let Some(list) = &files_from {
+                linter.lint_file_list(&read_file_list(list)?)
+            } else if is_stdin {
+                vec![linter.lint_reader(std::io::stdin(), linter::STDIN_NAME)?]
+            } else if Path::new(&path).is_dir() {
                 linter.lint_directory(&path)?
+            } else if is_archive {
+                linter.lint_archive(&path)?
             } else {
-                vec![linter.lint_file(&path)?]
+                linter.lint_file_with_includes(&path)?
             };
 
+            // Предпросмотр исправлений вместо отчёта: код выхода 1, если что-то изменилось бы
+            if diff {
+                let patches = formatter::diff_reports(&results, &linter.config);
+                for patch in &patches {
+                    print!("{}", patch);
+                }
+                std::process::exit(if patches.is_empty() { 0 } else { 1 });
+            }
+
             if fix {
-                formatter::auto_fix_files(&results, &linter.config)?;
+                let fixed = formatter::auto_fix_files(&results, &linter.config, fix_broken)?;
+                let summary = formatter::fix_summary(&fixed.fixes);
+
+                // С --print-fixed в stdout идёт только список изменённых файлов
+                if print_fixed {
+                    let json = output_format == exporter::OutputFormat::Json;
+                    print!("{}", formatter::modified_list(&fixed.modified, json));
+                    if json {
+                        println!();
+                    }
+                    if let Some(summary) = summary {
+                        eprintln!("{}", summary);
+                    }
+                } else {
+                    for file in &fixed.modified {
+                        println!("Fixed: {}", file);
+                    }
+                    if let Some(summary) = summary {
+                        println!("{}", summary);
+                    }
+                }
+            }
+
+            if print_fixed {
+                // Отчёт не смешивается со списком файлов: пишется только в --output-file
+                if output_file.is_some() && output_format != exporter::OutputFormat::Text {
+                    let output = exporter::Exporter::new(&results).export(output_format)?;
+                    write_output(&output, output_file.as_deref())?;
+                }
+            } else if output_format == exporter::OutputFormat::Text {
+                linter.print_results(&results, cli.verbose);
+            } else {
+                let output = exporter::Exporter::new(&results).export(output_format)?;
+                write_output(&output, output_file.as_deref())?;
             }
 
-            linter.print_results(&results);
+            if watch {
+                return watch::run(&linter, &path, cli.verbose);
+            }
 
-            if results.iter().any(|r| !r.passed) && !fix {
-                std::process::exit(1);
+            // Код выхода 1 означает ошибки без --fix; предупреждения учитываются
+            // только с --strict или сверх --max-warnings
+            let summary = exporter::Exporter::new(&results).summary();
+            let failed_files = results.iter().filter(|r| !r.passed).count();
+            let too_many_warnings = if cli.strict {
+                summary.warnings > 0
+            } else {
+                cli.max_warnings.is_some_and(|max| summary.warnings > max)
+            };
+            let exit_code = if (failed_files > 0 || too_many_warnings) && !fix { 1 } else { 0 };
+
+            if exit_json {
+                eprintln!("{}", exporter::exit_json(&summary, exit_code));
+            }
+
+            if exit_code != 0 {
+                if failed_files > 0 {
+                    eprintln!(
+                        "Exiting {}: {} error(s) in {} file(s) (fail-on=error)",
+                        exit_code, summary.errors, failed_files
+                    );
+                } else if cli.strict {
+                    eprintln!("Exiting {}: {} warning(s) (--strict)", exit_code, summary.warnings);
+                } else {
+                    eprintln!(
+                        "Exiting {}: {} warning(s), more than --max-warnings {}",
+                        exit_code, summary.warnings, cli.max_warnings.unwrap_or_default()
+                    );
+                }
+                std::process::exit(exit_code);
             }
         }
 
-        cli::Commands::Validate { path, schema: _ } => {
-            let result = linter.validate_file(&path)?;
+        cli::Commands::Validate { path, schema } => {
+            let result = linter.validate_file(&path, schema.as_deref())?;
             linter.print_validation_results(&result);
 
             if !result.valid {
@@ -49,22 +173,84 @@ fn main() -> Result<()> {
             }
         }
 
-        cli::Commands::Format { path, in_place } => {
-            formatter::format_files(&path, in_place, &linter.config)?;
+        cli::Commands::Format { path, in_place, jobs, print_fixed, diff } => {
+            let changed = if path == linter::STDIN_PATH {
+                if in_place {
+                    anyhow::bail!("Cannot format standard input in place");
+                }
+                let mut content = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .context("failed to read standard input")?;
+                let formatted = formatter::format_content(&content, linter::STDIN_NAME, &linter.config);
+
+                if diff {
+                    print!("{}", formatter::unified_diff(&content, &formatted, linter::STDIN_NAME));
+                } else {
+                    print!("{}", formatted);
+                }
+                usize::from(content != formatted)
+            } else {
+                formatter::format_files(&path, in_place, diff, jobs, print_fixed, &linter.config)?.len()
+            };
+
+            if diff && changed > 0 {
+                std::process::exit(1);
+            }
         }
 
-        cli::Commands::Config { generate } => {
+        cli::Commands::MergeReports { reports, output_format, output_file } => {
+            let merged = exporter::merge_reports(&reports)?;
+
+            if output_format == exporter::OutputFormat::Text {
+                linter.print_results(&merged, cli.verbose);
+            } else {
+                let output = exporter::Exporter::new(&merged).export(output_format)?;
+                write_output(&output, output_file.as_deref())?;
+            }
+        }
+
+        cli::Commands::Config { generate, output, force, output_format } => {
             if generate {
-                let default_config = Config::default();
-                let yaml = serde_yaml::to_string(&default_config)?;
-                std::fs::write(".yamllint.yaml", yaml)?;
-                println!("Generated default .yamllint.yaml");
+                Config::write_default(Path::new(&output), force)?;
+                println!("Generated default {}", output);
             } else {
-                println!("Current configuration:");
-                println!("{:#?}", linter.config);
+                println!("{}", linter.config.render(output_format)?);
             }
         }
     }
 
     Ok(())
 }
+
+/// Список файлов для `--files-from`: по пути на строке, пустые строки пропускаются
+fn read_file_list(list: &str) -> Result<Vec<String>> {
+    let mut content = String::new();
+
+    if list == linter::STDIN_PATH {
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("failed to read file list from standard input")?;
+    } else {
+        content = std::fs::read_to_string(list)
+            .with_context(|| format!("failed to read file list {}", list))?;
+    }
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Пишет отчёт в файл или, если файл не задан, в стандартный вывод
+fn write_output(output: &str, output_file: Option<&str>) -> Result<()> {
+    match output_file {
+        Some(file) => std::fs::write(file, output)
+            .with_context(|| format!("failed to write {}", file))?,
+        None => println!("{}", output),
+    }
+
+    Ok(())
+}
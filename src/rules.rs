@@ -1,13 +1,69 @@
-use crate::config::{Config, Severity};
+use crate::config::{self, BlockScalarStyle, BracketsRule, Config, NamingConvention, QuoteRequirement, QuoteType, Severity, YamlVersion};
+use crate::directives::Directives;
+use crate::locator::{format_path, PathIndex, PathSegment, Position};
+use crate::scanner::{self, Line, ScalarStyle, Token, TokenKind};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_yaml::{Value, Mapping};
-use std::collections::HashSet;
+use anyhow::Context;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 
-#[derive(Debug, Clone)]
+lazy_static! {
+    // Дата и метка времени в формате YAML 1.1 (тип timestamp)
+    static ref TIMESTAMP_RE: Regex = Regex::new(
+        r"^\d{4}-\d{1,2}-\d{1,2}(?:(?:[Tt]|[ \t]+)\d{1,2}:\d{2}:\d{2}(?:\.\d*)?(?:[ \t]*(?:Z|[-+]\d{1,2}(?::\d{2})?))?)?$"
+    ).unwrap();
+
+    // Число с незначащими нулями в начале: `007`, `-01.5` (но не `0`, `0.5`)
+    static ref LEADING_ZEROS_RE: Regex = Regex::new(r"^[-+]?0\d+(?:\.\d*)?$").unwrap();
+
+    // Восьмеричные числа: неявные YAML 1.1 (`0755`) и явные YAML 1.2 (`0o755`)
+    static ref IMPLICIT_OCTAL_RE: Regex = Regex::new(r"^0[0-7]+$").unwrap();
+    static ref EXPLICIT_OCTAL_RE: Regex = Regex::new(r"^0o[0-7]+$").unwrap();
+
+    // Особые и непереносимые записи дробных чисел для float-values
+    static ref INF_RE: Regex = Regex::new(r"^[-+]?\.(?:inf|Inf|INF)$").unwrap();
+    static ref NAN_RE: Regex = Regex::new(r"^\.(?:nan|NaN|NAN)$").unwrap();
+    static ref BARE_DECIMAL_RE: Regex = Regex::new(r"^[-+]?\.\d+(?:[eE][-+]?\d+)?$").unwrap();
+    static ref SCIENTIFIC_RE: Regex = Regex::new(r"^[-+]?(?:\.\d+|\d+(?:\.\d*)?)[eE][-+]?\d+$").unwrap();
+
+    // Десятичная дробь; группа 1 — цифры после точки
+    static ref FLOAT_RE: Regex = Regex::new(r"^[-+]?\d*\.(\d+)(?:[eE][-+]?\d+)?$").unwrap();
+
+    // Простой скаляр вида `key:value` — вероятно, пропущен пробел после `:`.
+    // URL (`http://`) и время (`12:30`) сюда не попадают
+    static ref MISSING_COLON_SPACE_RE: Regex = Regex::new(r"^[A-Za-z_][A-Za-z0-9_.-]*:[^\s/:]").unwrap();
+
+    // Соглашения об именах ключей для key-naming
+    static ref SNAKE_CASE_RE: Regex = Regex::new(r"^[a-z][a-z0-9]*(?:_[a-z0-9]+)*$").unwrap();
+    static ref CAMEL_CASE_RE: Regex = Regex::new(r"^[a-z][a-zA-Z0-9]*$").unwrap();
+    static ref KEBAB_CASE_RE: Regex = Regex::new(r"^[a-z][a-z0-9]*(?:-[a-z0-9]+)*$").unwrap();
+    static ref PASCAL_CASE_RE: Regex = Regex::new(r"^[A-Z][a-zA-Z0-9]*$").unwrap();
+
+    // Булевы значения YAML 1.1
+    static ref TRUTHY_VALUES: Vec<&'static str> = vec![
+        "y", "Y", "yes", "Yes", "YES", "n", "N", "no", "No", "NO",
+        "true", "True", "TRUE", "false", "False", "FALSE",
+        "on", "On", "ON", "off", "Off", "OFF",
+    ];
+
+    // Ссылки на переменные и очевидные заглушки вместо секрета
+    static ref SECRET_PLACEHOLDER_RE: Regex = Regex::new(
+        r"(?i)^(\$\{[^}]*\}|\$[A-Z_][A-Z0-9_]*|\{\{.*\}\}|<[^>]*>|\*+|x+|changeme|change_me|replace_?me|todo|null|~|none)$"
+    ).unwrap();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LintResult {
     pub file: String,
     pub line: usize,
     pub column: usize,
+    /// Длина нарушающего фрагмента в символах; 0 — известна только позиция
+    #[serde(default)]
+    pub length: usize,
     pub severity: Severity,
     pub rule: String,
     pub message: String,
@@ -24,43 +80,325 @@ impl LintResult {
     }
 }
 
+/// Справочные сведения о правиле для подробного вывода
+pub struct RuleInfo {
+    pub description: &'static str,
+    pub suggestion: &'static str,
+}
+
+pub fn rule_info(rule: &str) -> RuleInfo {
+    let (description, suggestion) = match rule {
+        "syntax" => ("The document must be valid YAML", "Fix the YAML syntax at the reported position"),
+        "indentation" => ("Indentation must be a multiple of the configured number of spaces", "Run with --fix to reindent"),
+        "trailing-spaces" => ("Lines must not end with whitespace", "Run with --fix to strip trailing whitespace"),
+        "line-length" => ("Lines must not exceed the configured maximum length", "Break the value into a block scalar or shorten it"),
+        "empty-lines" => ("Limits blank lines at the start, end and inside the document", "Run with --fix to remove extra blank lines"),
+        "implicit-dates" => ("Unquoted dates are parsed as timestamps rather than strings", "Quote the value to keep it a string"),
+        "port-range" => ("Port values must be integers between 1 and 65535", "Use a port number within 1-65535"),
+        "indent-sequence" => ("Sequences under a key must follow the configured indentation style", "Run with --fix to reindent the sequence"),
+        "final-line" => ("The file must end with content rather than a comment", "Move the comment above the content it describes or remove it"),
+        "block-scalar-style" => ("Block scalars must use the preferred style", "Switch the indicator between | and >"),
+        "stray-comma" => ("Plain scalars must not end with a comma outside flow collections", "Remove the comma or quote the value"),
+        "no-inline-secrets" => ("Secret-like keys must not hold literal values", "Reference an environment variable or a secret store instead"),
+        "duplicate-blocks" => ("Identical collections should not be repeated", "Extract the block into an anchor and reference it with an alias"),
+        "document-start" => ("The document start marker --- must be present or absent as configured", "Run with --fix to insert or remove the marker"),
+        "key-ordering" => ("Mapping keys must be sorted alphabetically", "Move the key before the one reported in the message"),
+        "truthy" => ("Only the allowed spellings of booleans may be used unquoted", "Use true/false or quote the value"),
+        "key-occurrence-limit" => ("A key may appear at most the configured number of times per document", "Remove the extra occurrences or raise the limit"),
+        "leading-zeros" => ("Unquoted numbers must not start with zeros that would be dropped", "Quote the value to keep it a string"),
+        "octal-values" => ("Unquoted numbers must not be octal literals that parsers read differently", "Quote the value, e.g. mode: \"0755\""),
+        "quoted-strings" => ("String values must follow the configured quoting policy", "Add or remove the quotes, or switch the quote style"),
+        "colons" => ("Keys must be followed by a colon and a single space", "Remove the spaces before the colon and keep one space after it"),
+        "hyphens" => ("Sequence indicators must be followed by a single space", "Remove the extra spaces after the dash"),
+        "brackets" => ("Spacing inside [ ] must stay within the configured limits", "Add or remove spaces after [ and before ]"),
+        "braces" => ("Spacing inside { } must stay within the configured limits", "Add or remove spaces after { and before }"),
+        "commas" => ("Commas in flow collections must have no space before and one space after", "Move the spaces from before the comma to after it"),
+        "new-line-at-end-of-file" => ("Files must end with exactly one newline character", "Add the missing newline or remove the trailing blank lines"),
+        "line-endings" => ("All lines must use the configured line ending", "Convert the file to the expected line endings"),
+        "block-scalar-chomping" => ("Block scalars must state how trailing newlines are handled", "Add - or + after the block scalar indicator"),
+        "float-precision" => ("Floats must not have more decimal places than configured", "Round the value to a sensible precision"),
+        "float-values" => ("Floats must avoid forms that YAML parsers handle inconsistently", "Write the number as a plain decimal with a leading digit, or quote it"),
+        "empty-values" => ("Mapping keys must have an explicit value", "Write the value, or null if it is meant to be empty"),
+        "merge-keys" => ("Merge keys (<<) are not supported by every YAML consumer", "Copy the merged keys into the mapping explicitly"),
+        "strict-indent" => ("Each nesting level must be indented exactly one step from its parent", "Align the line with the expected column"),
+        "max-depth" => ("Values must not be nested deeper than the configured number of levels", "Flatten the structure or move the nested part into a separate file"),
+        "document-start-consistency" => ("Files in a directory must agree on whether documents start with ---", "Add or remove the marker to match the other files"),
+        "compare-to-defaults" => ("Values must match the reference defaults file", "Restore the default value or update the reference"),
+        "duplicate-named-items" => ("Items of a list of mappings must have unique identifying fields", "Rename or merge the duplicate items"),
+        "key-order-template" => ("Well-known top-level keys must follow the configured order", "Move the key before the one reported in the message"),
+        "control-chars" => ("Values must not contain invisible control characters", "Remove the character or write it as an escape in a double-quoted string"),
+        "forbidden-keys" => ("Keys from the forbidden list must not appear", "Remove the key or move the value out of the committed config"),
+        "key-naming" => ("Mapping keys must follow the configured naming convention", "Rename the key or add it to key_naming.ignore"),
+        "anchors" => ("Anchors must be declared before use, used, and unique within a document", "Declare the anchor before the alias, or remove the unused or repeated anchor"),
+        "files-from" => ("Every path passed with --files-from must be an existing YAML file", "Fix the path or drop it from the list"),
+        "include" => ("Files referenced by include tags must exist", "Fix the path relative to the including file"),
+        "comments" => ("Comments must start with a space and be separated from content", "Insert a space after # or before the comment"),
+        "comments-indentation" => ("Comments must be indented like the content they describe", "Align the comment with the next line"),
+        "required-fields" => ("Files matching a pattern must define the configured fields", "Add the missing field"),
+        "value-types" => ("Quoted strings must not look like numbers or booleans", "Drop the quotes to use the native type"),
+        "duplicates" => ("Keys must be unique within a mapping", "Remove or rename one of the keys"),
+        "environment-markers" => ("Environment-specific files must set the configured values", "Set the value expected for this environment"),
+        "homogeneous-values" => ("Mappings should not mix scalar types across their values", "Quote the outliers or split the mapping"),
+        _ => ("", ""),
+    };
+
+    RuleInfo { description, suggestion }
+}
+
+/// Смещение в байтах для позиции строка/колонка (колонка в символах)
+pub fn byte_offset(content: &str, line: usize, column: usize) -> usize {
+    let mut offset = 0;
+
+    for (number, text) in content.split_inclusive('\n').enumerate() {
+        if number + 1 == line {
+            return offset + text.char_indices().nth(column.saturating_sub(1)).map_or(text.len(), |(i, _)| i);
+        }
+        offset += text.len();
+    }
+
+    offset
+}
+
 pub struct RuleChecker {
     config: Config,
+    /// Первый документ эталонного файла правила compare-to-defaults
+    defaults: Option<Value>,
+    /// Правила из `rule_scopes` и пути, к которым они применяются
+    scopes: HashMap<String, GlobSet>,
+    /// Шаблоны `forbidden_keys.keys`, без учёта регистра
+    forbidden_keys: GlobSet,
+    /// Шаблоны путей файлов из `required_fields.paths`
+    required_paths: PatternSet,
+    /// Ключи-исключения из `key_naming.ignore`
+    naming_ignored: GlobSet,
+    /// Шаблоны путей ключей из `key_occurrence_limit.limits`
+    occurrence_limits: PatternSet,
+    /// Шаблоны путей последовательностей из `duplicate_named_items.sequences`
+    named_sequences: GlobSet,
+    /// Шаблоны путей файлов из `environment_markers.files`
+    environment_files: PatternSet,
 }
 
 impl RuleChecker {
-    pub fn new(config: Config) -> Self {
-        RuleChecker { config }
+    pub fn new(config: Config) -> anyhow::Result<Self> {
+        let rule = &config.rules.compare_to_defaults;
+        let defaults = match &rule.reference {
+            Some(reference) if rule.level != Severity::Off => {
+                let path = config.resolve_path(reference);
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read defaults {}", path.display()))?;
+                let document = serde_yaml::Deserializer::from_str(&content).next();
+                let value = match document {
+                    Some(document) => Value::deserialize(document)
+                        .with_context(|| format!("failed to parse defaults {}", path.display()))?,
+                    None => Value::Null,
+                };
+                Some(value)
+            }
+            _ => None,
+        };
+
+        let scopes = config.rule_scope_sets()?;
+        let forbidden_keys = glob_set("forbidden-keys", &config.rules.forbidden_keys.keys,
+                                      |p| GlobBuilder::new(p).case_insensitive(true).build())?;
+        let required_paths = PatternSet::new("required-fields", config.rules.required_fields.paths.keys(),
+                                             |p| Glob::new(&config::path_glob(p)))?;
+        let naming_ignored = glob_set("key-naming", &config.rules.key_naming.ignore, Glob::new)?;
+        // Сегменты пути сопоставляются как компоненты пути файла
+        let occurrence_limits = PatternSet::new("key-occurrence-limit", config.rules.key_occurrence_limit.limits.keys(),
+                                                |p| Glob::new(&p.replace('.', "/")))?;
+        let named_sequences = glob_set("duplicate-named-items", &config.rules.duplicate_named_items.sequences,
+                                       |p| Glob::new(&p.replace('.', "/")))?;
+        let environment_files = PatternSet::new("environment-markers", config.rules.environment_markers.files.keys(),
+                                                Glob::new)?;
+
+        Ok(RuleChecker {
+            config, defaults, scopes, forbidden_keys, required_paths, naming_ignored, occurrence_limits,
+            named_sequences, environment_files,
+        })
     }
 
     pub fn check_file(&self, content: &str, file_path: &str) -> Vec<LintResult> {
+        let lines = scanner::scan(content);
+        let directives = Directives::parse(&lines);
+
+        let mut results = self.check_lines(content, &lines, file_path);
+        results.retain(|r| !directives.is_disabled(&r.rule, r.line) && self.in_scope(&r.rule, file_path));
+        results
+    }
+
+    /// Шаблоны `rule_scopes` сопоставляются с путём относительно текущего каталога
+    fn in_scope(&self, rule: &str, file_path: &str) -> bool {
+        let path = file_path.trim_start_matches("./");
+        self.scopes.get(rule).is_none_or(|scope| scope.is_match(path))
+    }
+
+    /// Межфайловая проверка каталога: все файлы начинаются с `---` или ни один.
+    /// Сообщает о файлах, отличающихся от большинства; при равенстве молчит
+    pub fn check_document_start_consistency(&self, files: &[(&str, &str)]) -> Vec<LintResult> {
         let mut results = vec![];
+        let rule = &self.config.rules.document_start_consistency;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let mut styles = vec![];
+
+        for (file_path, content) in files {
+            let lines = scanner::scan(content);
+
+            if Directives::parse(&lines).is_disabled("document-start-consistency", 1)
+                || !self.in_scope("document-start-consistency", file_path) {
+                continue;
+            }
+
+            if let Some(first) = scanner::first_content_line(&lines) {
+                let has_marker = lines[first].tokens[0].kind == TokenKind::DocumentStart;
+                styles.push((*file_path, has_marker, lines[first].text.to_string()));
+            }
+        }
+
+        let with_marker = styles.iter().filter(|(_, has_marker, _)| *has_marker).count();
+        let without_marker = styles.len() - with_marker;
+
+        if with_marker == without_marker {
+            return results;
+        }
+
+        let majority = with_marker > without_marker;
+
+        for (file_path, has_marker, snippet) in styles {
+            if has_marker == majority {
+                continue;
+            }
+
+            let message = if majority {
+                format!("Missing document start \"---\" used by {} of {} files in the directory", with_marker, with_marker + without_marker)
+            } else {
+                format!("Document start \"---\" is omitted by {} of {} files in the directory", without_marker, with_marker + without_marker)
+            };
 
-        // Проверка синтаксиса
-        if let Err(e) = serde_yaml::from_str::<Value>(content) {
             results.push(LintResult {
                 file: file_path.to_string(),
                 line: 1,
                 column: 1,
-                severity: Severity::Error,
-                rule: "syntax".to_string(),
-                message: format!("Syntax error: {}", e),
-                snippet: content.lines().next().unwrap_or("").to_string(),
+                length: 0,
+                severity: rule.level.clone(),
+                rule: "document-start-consistency".to_string(),
+                message,
+                snippet,
             });
-            return results;
         }
 
-        // Базовые проверки на уровне текста
+        results
+    }
+
+    fn check_lines(&self, content: &str, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let index = PathIndex::build(lines);
+
+        // Базовые проверки на уровне текста не требуют разбора, поэтому
+        // выполняются и для файлов с синтаксической ошибкой. Среди них и
+        // табуляция в отступе — частая причина такой ошибки
         results.extend(self.check_indentation(content, file_path));
+        results.extend(self.check_tab_indentation(content, file_path));
         results.extend(self.check_trailing_spaces(content, file_path));
         results.extend(self.check_line_length(content, file_path));
         results.extend(self.check_empty_lines(content, file_path));
+        results.extend(self.check_control_chars(content, file_path));
+        results.extend(self.check_final_newline(content, file_path));
+        results.extend(self.check_line_endings(content, file_path));
+        // Ссылка на необъявленный якорь — ошибка разбора, поэтому до него
+        results.extend(self.check_anchors(lines, file_path));
+
+        // Проверка синтаксиса: каждый документ потока разбирается отдельно
+        let mut values = vec![];
+        for document in serde_yaml::Deserializer::from_str(content) {
+            match Value::deserialize(document) {
+                Ok(value) => values.push(value),
+                Err(e) => {
+                    // Повторяющиеся ключи serde_yaml считает ошибкой разбора;
+                    // сообщаем о каждом из них с точной позицией
+                    let duplicates = self.check_duplicates(&index, lines, file_path);
+                    if e.to_string().contains("duplicate entry") && !duplicates.is_empty() {
+                        results.extend(duplicates);
+                        return results;
+                    }
+
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: 1,
+                        column: 1,
+                        length: 0,
+                        severity: Severity::Error,
+                        rule: "syntax".to_string(),
+                        message: format!("Syntax error: {}", e),
+                        snippet: content.lines().next().unwrap_or("").to_string(),
+                    });
+                    return results;
+                }
+            }
+        }
+
+        // Проверки на уровне токенов
+        let version = scanner::yaml_directive(lines)
+            .and_then(YamlVersion::parse)
+            .unwrap_or(self.config.yaml_version);
+
+        results.extend(self.check_implicit_dates(lines, version, file_path));
+        results.extend(self.check_port_range(lines, file_path));
+        results.extend(self.check_indent_sequence(lines, file_path));
+        results.extend(self.check_strict_indent(lines, file_path));
+        results.extend(self.check_final_line(lines, file_path));
+        results.extend(self.check_block_scalar_style(lines, file_path));
+        results.extend(self.check_block_scalar_chomping(lines, file_path));
+        results.extend(self.check_stray_comma(lines, file_path));
+        results.extend(self.check_inline_secrets(lines, file_path));
+        results.extend(self.check_comments_indentation(lines, file_path));
+        results.extend(self.check_comments(lines, file_path));
+        results.extend(self.check_document_start(lines, file_path));
+        results.extend(self.check_truthy(lines, version, file_path));
+        results.extend(self.check_leading_zeros(lines, file_path));
+        results.extend(self.check_octal_values(lines, file_path));
+        results.extend(self.check_quoted_strings(lines, version, file_path));
+        results.extend(self.check_colons(lines, file_path));
+        results.extend(self.check_hyphens(lines, file_path));
+        results.extend(self.check_brackets(lines, file_path));
+        results.extend(self.check_braces(lines, file_path));
+        results.extend(self.check_commas(lines, file_path));
+        results.extend(self.check_float_precision(lines, file_path));
+        results.extend(self.check_float_values(lines, file_path));
+        results.extend(self.check_empty_values(lines, file_path));
+        results.extend(self.check_merge_keys(lines, file_path));
+
+        // Семантические проверки на уровне AST, для каждого документа отдельно
+        results.extend(self.check_duplicates(&index, lines, file_path));
+
+        for (number, value) in values.iter().enumerate() {
+            let doc = Document { value, number, index: &index, lines };
+            let mut semantic = vec![];
 
-        // Семантические проверки на уровне AST
-        if let Ok(value) = serde_yaml::from_str::<Value>(content) {
-            results.extend(self.check_required_fields(&value, file_path));
-            results.extend(self.check_value_types(&value, file_path));
-            results.extend(self.check_duplicates(&value, file_path));
+            semantic.extend(self.check_required_fields(&doc, file_path));
+            semantic.extend(self.check_value_types(&doc, file_path));
+            semantic.extend(self.check_environment_markers(&doc, file_path));
+            semantic.extend(self.check_homogeneous_values(&doc, file_path));
+            semantic.extend(self.check_duplicate_blocks(&doc, file_path));
+            semantic.extend(self.check_key_ordering(&doc, file_path));
+            semantic.extend(self.check_key_order_template(&doc, file_path));
+            semantic.extend(self.check_forbidden_keys(&doc, file_path));
+            semantic.extend(self.check_key_naming(&doc, file_path));
+            semantic.extend(self.check_key_occurrence_limit(&doc, file_path));
+            semantic.extend(self.check_max_depth(&doc, file_path));
+            semantic.extend(self.check_compare_to_defaults(&doc, file_path));
+            semantic.extend(self.check_duplicate_named_items(&doc, file_path));
+
+            if values.len() > 1 {
+                for result in &mut semantic {
+                    result.message = format!("{} (document {})", result.message, number + 1);
+                }
+            }
+            results.extend(semantic);
         }
 
         results
@@ -68,9 +406,14 @@ impl RuleChecker {
 
     fn check_indentation(&self, content: &str, file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
-        let expected_spaces = self.config.rules.indentation.spaces;
+        let rule = &self.config.rules.indentation;
+        let expected_spaces = rule.spaces;
         let _space_str = " ".repeat(expected_spaces); // Сохраняем для возможного использования
 
+        if rule.level == Severity::Off {
+            return results;
+        }
+
         for (i, line) in content.lines().enumerate() {
             let line_num = i + 1;
 
@@ -79,6 +422,11 @@ impl RuleChecker {
                 continue;
             }
 
+            // Табуляцию в отступе проверяет check_tab_indentation
+            if leading_whitespace(line).contains('\t') {
+                continue;
+            }
+
             // Проверяем отступы
             if line.starts_with(' ') {
                 let leading_spaces = line.len() - line.trim_start().len();
@@ -86,8 +434,10 @@ impl RuleChecker {
                     results.push(LintResult {
                         file: file_path.to_string(),
                         line: line_num,
-                        column: 1,
-                        severity: Severity::Error,
+                        // Колонка первого символа после отступа
+                        column: leading_spaces + 1,
+                        length: 0,
+                        severity: rule.level.clone(),
                         rule: "indentation".to_string(),
                         message: format!("Indentation should be multiples of {} spaces", expected_spaces),
                         snippet: line.to_string(),
@@ -99,18 +449,66 @@ impl RuleChecker {
         results
     }
 
+    /// Табуляция в отступе. Смесь пробелов и табуляции сообщается всегда,
+    /// даже при `forbid_tabs: false`: её ширина зависит от парсера и редактора
+    fn check_tab_indentation(&self, content: &str, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.indentation;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for (i, line) in content.lines().enumerate() {
+            let indent = leading_whitespace(line);
+            let Some(tab) = indent.find('\t') else { continue };
+
+            let (column, message) = if indent.contains(' ') {
+                // Колонка первого символа, отличного от начального
+                let first = indent.as_bytes()[0];
+                let mix = indent.bytes().position(|b| b != first).unwrap_or(0);
+                (mix + 1, "Mixed spaces and tabs in indentation")
+            } else if rule.forbid_tabs {
+                (tab + 1, "Tab character used for indentation")
+            } else {
+                continue;
+            };
+
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: i + 1,
+                column,
+                length: 1,
+                severity: rule.level.clone(),
+                rule: "indentation".to_string(),
+                message: message.to_string(),
+                snippet: line.to_string(),
+            });
+        }
+
+        results
+    }
+
     fn check_trailing_spaces(&self, content: &str, file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
+        let level = &self.config.rules.trailing_spaces.level;
+
+        if *level == Severity::Off {
+            return results;
+        }
 
         for (i, line) in content.lines().enumerate() {
             let line_num = i + 1;
 
-            if line.ends_with(' ') || line.ends_with('\t') {
+            let content_end = line.trim_end_matches([' ', '\t']);
+            if content_end.len() != line.len() {
                 results.push(LintResult {
                     file: file_path.to_string(),
                     line: line_num,
-                    column: line.len(),
-                    severity: self.config.rules.trailing_spaces.level.clone(),
+                    // Колонка первого пробельного символа в символах, а не байтах
+                    column: content_end.chars().count() + 1,
+                    length: line.chars().count() - content_end.chars().count(),
+                    severity: level.clone(),
                     rule: "trailing-spaces".to_string(),
                     message: "Trailing spaces are not allowed".to_string(),
                     snippet: line.to_string(),
@@ -123,19 +521,31 @@ impl RuleChecker {
 
     fn check_line_length(&self, content: &str, file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
-        let max_length = self.config.rules.line_length.max;
+        let rule = &self.config.rules.line_length;
+        let max_length = rule.max;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
 
         for (i, line) in content.lines().enumerate() {
             let line_num = i + 1;
 
-            if line.len() > max_length {
+            // Длина в символах: многобайтовые символы UTF-8 считаются за один
+            let length = line.chars().count();
+
+            if length > max_length {
+                // Колонка первого символа, выходящего за лимит
+                let column = max_length + 1;
+
                 results.push(LintResult {
                     file: file_path.to_string(),
                     line: line_num,
-                    column: max_length + 1,
-                    severity: Severity::Warning,
+                    column,
+                    length: length - max_length,
+                    severity: rule.level.clone(),
                     rule: "line-length".to_string(),
-                    message: format!("Line too long ({} > {})", line.len(), max_length),
+                    message: format!("Line too long ({} > {})", length, max_length),
                     snippet: line.to_string(),
                 });
             }
@@ -146,21 +556,27 @@ impl RuleChecker {
 
     fn check_empty_lines(&self, content: &str, file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
+        let rule = &self.config.rules.empty_lines;
         let lines: Vec<&str> = content.lines().collect();
         let mut consecutive_empty = 0;
 
+        if rule.level == Severity::Off {
+            return results;
+        }
+
         for (i, line) in lines.iter().enumerate() {
             let line_num = i + 1;
 
             if line.trim().is_empty() {
                 consecutive_empty += 1;
 
-                if consecutive_empty > self.config.rules.empty_lines.max_consecutive {
+                if consecutive_empty > rule.max_consecutive {
                     results.push(LintResult {
                         file: file_path.to_string(),
                         line: line_num,
                         column: 1,
-                        severity: Severity::Warning,
+                        length: 0,
+                        severity: rule.level.clone(),
                         rule: "empty-lines".to_string(),
                         message: format!("Too many consecutive empty lines ({})", consecutive_empty),
                         snippet: "".to_string(),
@@ -177,12 +593,13 @@ impl RuleChecker {
             start_empty += 1;
         }
 
-        if start_empty > self.config.rules.empty_lines.max_start {
+        if start_empty > rule.max_start {
             results.push(LintResult {
                 file: file_path.to_string(),
                 line: 1,
                 column: 1,
-                severity: Severity::Warning,
+                length: 0,
+                severity: rule.level.clone(),
                 rule: "empty-lines".to_string(),
                 message: format!("Too many empty lines at start of file ({})", start_empty),
                 snippet: "".to_string(),
@@ -192,140 +609,2714 @@ impl RuleChecker {
         results
     }
 
-    fn check_required_fields(&self, value: &Value, file_path: &str) -> Vec<LintResult> {
+    /// Смотрит на исходный текст: `lines()` не различает файлы
+    /// с переводом строки в конце и без него
+    fn check_final_newline(&self, content: &str, file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
+        let rule = &self.config.rules.new_line_at_end_of_file;
 
-        for (pattern, required_fields) in &self.config.rules.required_fields.paths {
-            // Простая проверка паттерна (можно заменить на glob)
-            if file_path.contains(pattern.trim_matches('*').trim_matches('/')) {
-                self.check_required_in_value(value, required_fields, file_path, &mut results);
-            }
+        if rule.level == Severity::Off || !rule.require_final_newline || content.is_empty() {
+            return results;
         }
 
+        let body = content.trim_end_matches([' ', '\t', '\r', '\n']);
+        let last_line = body.lines().count().max(1);
+        let newlines = content[body.len()..].matches('\n').count();
+
+        let (line, message) = match newlines {
+            0 => (last_line, "No new line character at the end of file".to_string()),
+            1 => return results,
+            n => (last_line + 2, format!("Too many blank lines at end of file ({})", n - 1)),
+        };
+
+        results.push(LintResult {
+            file: file_path.to_string(),
+            line,
+            column: 1,
+            length: 0,
+            severity: rule.level.clone(),
+            rule: "new-line-at-end-of-file".to_string(),
+            message,
+            snippet: "".to_string(),
+        });
+
         results
     }
 
-    fn check_required_in_value(&self, value: &Value, required_fields: &[String],
-                               file_path: &str, results: &mut Vec<LintResult>) {
-        if let Value::Mapping(mapping) = value {
-            for field in required_fields {
-                let parts: Vec<&str> = field.split('.').collect();
-                self.check_nested_field(mapping, &parts, file_path, results);
-            }
-        }
-    }
+    /// Одно нарушение на файл: в первой строке с чужим переводом строки
+    fn check_line_endings(&self, content: &str, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.line_endings;
 
-    fn check_nested_field(&self, mapping: &Mapping, parts: &[&str],
-                          file_path: &str, results: &mut Vec<LintResult>) {
-        if parts.is_empty() {
-            return;
+        if rule.level == Severity::Off {
+            return results;
         }
 
-        let key = parts[0];
-        let key_value = Value::String(key.to_string());
+        let expected = rule.style.resolve(content);
+        let name = |ending: &str| if ending == "\r\n" { "\\r\\n (dos)" } else { "\\n (unix)" };
+
+        let mismatched: Vec<(usize, &str)> = content
+            .split_inclusive('\n')
+            .enumerate()
+            .filter(|(_, line)| line.ends_with('\n') && line.ends_with("\r\n") != (expected == "\r\n"))
+            .collect();
+
+        if let Some((index, line)) = mismatched.first() {
+            let text = line.trim_end_matches(['\r', '\n']);
+            let found = if line.ends_with("\r\n") { "\r\n" } else { "\n" };
 
-        if !mapping.contains_key(&key_value) {
             results.push(LintResult {
                 file: file_path.to_string(),
-                line: 1,
-                column: 1,
-                severity: Severity::Error,
-                rule: "required-fields".to_string(),
-                message: format!("Missing required field: {}", key),
-                snippet: "".to_string(),
+                line: index + 1,
+                column: text.chars().count() + 1,
+                length: 0,
+                severity: rule.level.clone(),
+                rule: "line-endings".to_string(),
+                message: format!(
+                    "Wrong line ending: expected {}, found {} on {} line(s)",
+                    name(expected), name(found), mismatched.len()
+                ),
+                snippet: text.to_string(),
             });
-            return;
         }
 
-        if parts.len() > 1 {
-            if let Some(sub_value) = mapping.get(&key_value) {
-                if let Value::Mapping(sub_mapping) = sub_value {
-                    self.check_nested_field(sub_mapping, &parts[1..], file_path, results);
+        results
+    }
+
+    /// Якоря действуют в пределах документа, поэтому объявления сбрасываются на `---` и `...`
+    fn check_anchors(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let rule = &self.config.rules.anchors;
+
+        if rule.level == Severity::Off {
+            return vec![];
+        }
+
+        let mut violations: Vec<AnchorViolation> = vec![];
+        // Объявления документа в порядке появления и признак использования
+        let mut declared: Vec<AnchorUse> = vec![];
+
+        for line in lines {
+            for token in &line.tokens {
+                let name = token.text.get(1..).unwrap_or_default();
+
+                match token.kind {
+                    TokenKind::DocumentStart | TokenKind::DocumentEnd => {
+                        close_anchor_scope(&mut declared, &mut violations, rule.forbid_unused);
+                    }
+                    TokenKind::Anchor => {
+                        if rule.forbid_duplicated && declared.iter().any(|(_, t, _)| &t.text[1..] == name) {
+                            violations.push((line, token, format!("Anchor '{}' is already declared in this document", name)));
+                        }
+                        declared.push((line, token, false));
+                    }
+                    TokenKind::Alias => {
+                        // Ссылка указывает на последнее объявление с этим именем
+                        match declared.iter_mut().rev().find(|(_, t, _)| &t.text[1..] == name) {
+                            Some((_, _, used)) => *used = true,
+                            None if rule.forbid_undeclared_aliases => {
+                                violations.push((line, token, format!("Alias '{}' refers to an undeclared anchor", name)));
+                            }
+                            None => {}
+                        }
+                    }
+                    _ => {}
                 }
             }
         }
+        close_anchor_scope(&mut declared, &mut violations, rule.forbid_unused);
+
+        violations
+            .into_iter()
+            .map(|(line, token, message)| LintResult {
+                file: file_path.to_string(),
+                line: line.number,
+                column: token.column,
+                length: token.text.chars().count(),
+                severity: rule.level.clone(),
+                rule: "anchors".to_string(),
+                message,
+                snippet: line.text.to_string(),
+            })
+            .collect()
     }
 
-    fn check_value_types(&self, value: &Value, file_path: &str) -> Vec<LintResult> {
+    /// Управляющие символы U+0000–U+001F, кроме табуляции и `\r` перед концом строки
+    fn check_control_chars(&self, content: &str, file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
-        self.visit_value(value, file_path, &mut results);
+        let level = &self.config.rules.control_chars.level;
+
+        if *level == Severity::Off {
+            return results;
+        }
+
+        for (i, line) in content.lines().enumerate() {
+            let text = line.strip_suffix('\r').unwrap_or(line);
+
+            for (column, c) in text.chars().enumerate() {
+                if c < '\u{20}' && c != '\t' {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: i + 1,
+                        column: column + 1,
+                        length: 1,
+                        severity: level.clone(),
+                        rule: "control-chars".to_string(),
+                        message: format!("Control character U+{:04X} in content", c as u32),
+                        snippet: line.to_string(),
+                    });
+                }
+            }
+        }
+
         results
     }
 
-    fn visit_value(&self, value: &Value, file_path: &str, results: &mut Vec<LintResult>) {
-        match value {
-            Value::String(s) => {
-                // Проверка на boolean строки
-                if self.config.rules.value_types.check_bool_values {
-                    let lower = s.to_lowercase();
-                    if lower == "true" || lower == "false" || lower == "yes" || lower == "no" {
-                        results.push(LintResult {
-                            file: file_path.to_string(),
-                            line: 1,
-                            column: 1,
-                            severity: Severity::Warning,
-                            rule: "value-types".to_string(),
-                            message: format!("Boolean-like string: '{}'. Consider using boolean type.", s),
-                            snippet: s.to_string(),
-                        });
-                    }
+    fn check_implicit_dates(&self, lines: &[Line], version: YamlVersion, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let level = &self.config.rules.implicit_dates.level;
+
+        // Тип timestamp есть только в YAML 1.1
+        if *level == Severity::Off || version == YamlVersion::V1_2 {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                // Даты в кавычках остаются строками
+                if token.kind == TokenKind::Scalar(ScalarStyle::Plain) && TIMESTAMP_RE.is_match(token.text) {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: token.column,
+                        length: token.text.chars().count(),
+                        severity: level.clone(),
+                        rule: "implicit-dates".to_string(),
+                        message: format!("Unquoted date '{}' is parsed as a timestamp. Quote it to keep a string.", token.text),
+                        snippet: line.text.to_string(),
+                    });
                 }
+            }
+        }
 
-                // Проверка на числовые строки
-                if self.config.rules.value_types.strict_numbers {
-                    if s.parse::<i64>().is_ok() || s.parse::<f64>().is_ok() {
-                        results.push(LintResult {
-                            file: file_path.to_string(),
-                            line: 1,
-                            column: 1,
-                            severity: Severity::Warning,
-                            rule: "value-types".to_string(),
-                            message: format!("Number-like string: '{}'. Consider using number type.", s),
-                            snippet: s.to_string(),
-                        });
-                    }
+        results
+    }
+
+    /// Каждый вложенный уровень должен быть ровно на `indentation.spaces`
+    /// правее родительского: отступы 2→4→6 и 2→3 по отдельности кратны
+    /// шагу или согласованы, но вместе нет
+    fn check_strict_indent(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let level = &self.config.rules.strict_indent.level;
+        let spaces = self.config.rules.indentation.spaces;
+
+        if *level == Severity::Off {
+            return results;
+        }
+
+        // Колонки (с 0) открытых блочных узлов от корня к текущему
+        let mut levels: Vec<usize> = vec![];
+        let mut flow_depth = 0usize;
+
+        for line in lines {
+            let in_flow = flow_depth > 0;
+
+            for token in &line.tokens {
+                match token.kind {
+                    TokenKind::FlowStart => flow_depth += 1,
+                    TokenKind::FlowEnd => flow_depth = flow_depth.saturating_sub(1),
+                    TokenKind::DocumentStart | TokenKind::DocumentEnd => levels.clear(),
+                    _ => {}
                 }
             }
 
-            Value::Mapping(mapping) => {
-                for (_, v) in mapping {
-                    self.visit_value(v, file_path, results);
+            // Учитываются строки, начинающиеся с блочного ключа или элемента `-`
+            let Some(first) = line.tokens.first() else { continue };
+            if in_flow || !matches!(first.kind, TokenKind::Key(_) | TokenKind::SequenceEntry) {
+                continue;
+            }
+
+            let column = first.column - 1;
+            while levels.last().is_some_and(|&top| top > column) {
+                levels.pop();
+            }
+
+            if levels.last() != Some(&column) {
+                let expected = levels.last().map_or(0, |&parent| parent + spaces);
+                if column != expected {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: first.column,
+                        length: first.text.chars().count(),
+                        severity: level.clone(),
+                        rule: "strict-indent".to_string(),
+                        message: format!("Wrong indentation: expected {} but found {}", expected, column),
+                        snippet: line.text.to_string(),
+                    });
                 }
+                levels.push(column);
             }
 
-            Value::Sequence(seq) => {
-                for v in seq {
-                    self.visit_value(v, file_path, results);
+            // `- key: value` и `- - item` открывают вложенные уровни на той же строке
+            for pair in line.tokens.windows(2) {
+                if pair[0].kind == TokenKind::SequenceEntry
+                    && matches!(pair[1].kind, TokenKind::Key(_) | TokenKind::SequenceEntry)
+                {
+                    levels.push(pair[1].column - 1);
                 }
             }
+        }
 
-            _ => {}
+        results
+    }
+
+    fn check_truthy(&self, lines: &[Line], version: YamlVersion, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.truthy;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        // В YAML 1.2 `yes`, `on` и т.п. — обычные строки
+        let truthy: &[&str] = match version {
+            YamlVersion::V1_1 => &TRUTHY_VALUES,
+            YamlVersion::V1_2 => &["true", "True", "TRUE", "false", "False", "FALSE"],
+        };
+
+        for line in lines {
+            for token in &line.tokens {
+                // Значения в кавычках — строки при любой версии YAML
+                let is_checked = match token.kind {
+                    TokenKind::Scalar(ScalarStyle::Plain) => true,
+                    TokenKind::Key(ScalarStyle::Plain) => rule.check_keys,
+                    _ => false,
+                };
+
+                if is_checked && truthy.contains(&token.text) && !rule.allowed_values.iter().any(|v| v == token.text) {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: token.column,
+                        length: token.text.chars().count(),
+                        severity: rule.level.clone(),
+                        rule: "truthy".to_string(),
+                        message: format!(
+                            "Truthy value '{}' is not one of [{}]",
+                            token.text, rule.allowed_values.join(", ")
+                        ),
+                        snippet: line.text.to_string(),
+                    });
+                }
+            }
         }
+
+        results
     }
 
-    fn check_duplicates(&self, value: &Value, file_path: &str) -> Vec<LintResult> {
+    fn check_leading_zeros(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
         let mut results = vec![];
+        let level = &self.config.rules.leading_zeros.level;
+
+        if *level == Severity::Off {
+            return results;
+        }
 
-        if let Value::Mapping(mapping) = value {
-            let mut seen_keys = HashSet::new();
+        // `0755` точнее описывает octal-values, если оно включено
+        let octal = &self.config.rules.octal_values;
+        let reported_as_octal = |text: &str| {
+            octal.level != Severity::Off && octal.forbid_implicit && IMPLICIT_OCTAL_RE.is_match(text)
+        };
 
-            for (k, _) in mapping {
-                if let Value::String(s) = k {
-                    if !seen_keys.insert(s) {
-                        results.push(LintResult {
-                            file: file_path.to_string(),
-                            line: 1,
-                            column: 1,
-                            severity: self.config.rules.duplicates.level.clone(),
-                            rule: "duplicates".to_string(),
-                            message: format!("Duplicate key: '{}'", s),
-                            snippet: s.to_string(),
-                        });
-                    }
+        for line in lines {
+            for token in &line.tokens {
+                if token.kind == TokenKind::Scalar(ScalarStyle::Plain)
+                    && LEADING_ZEROS_RE.is_match(token.text)
+                    && !reported_as_octal(token.text)
+                {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: token.column,
+                        length: token.text.chars().count(),
+                        severity: level.clone(),
+                        rule: "leading-zeros".to_string(),
+                        message: format!("Number '{}' has leading zeros that may be lost. Quote it to keep them.", token.text),
+                        snippet: line.text.to_string(),
+                    });
                 }
             }
         }
 
         results
     }
+
+    fn check_octal_values(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.octal_values;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                if token.kind != TokenKind::Scalar(ScalarStyle::Plain) {
+                    continue;
+                }
+
+                let kind = if rule.forbid_implicit && IMPLICIT_OCTAL_RE.is_match(token.text) {
+                    "implicit"
+                } else if rule.forbid_explicit && EXPLICIT_OCTAL_RE.is_match(token.text) {
+                    "explicit"
+                } else {
+                    continue;
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column,
+                    length: token.text.chars().count(),
+                    severity: rule.level.clone(),
+                    rule: "octal-values".to_string(),
+                    message: format!("Forbidden {} octal value '{}'. Quote it if it is meant as a string.", kind, token.text),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    /// Проверяются только значения: ключи и скаляры с явным тегом пропускаются
+    fn check_quoted_strings(&self, lines: &[Line], version: YamlVersion, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.quoted_strings;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for (i, token) in line.tokens.iter().enumerate() {
+                let TokenKind::Scalar(style) = token.kind else { continue };
+
+                if i > 0 && line.tokens[i - 1].kind == TokenKind::Tag {
+                    continue;
+                }
+
+                let message = match style {
+                    // Числа, булевы и null — не строки, кавычки им не нужны
+                    ScalarStyle::Plain => {
+                        if rule.required != QuoteRequirement::Always || !is_plain_string(token.text, version) {
+                            continue;
+                        }
+                        "String value is not quoted".to_string()
+                    }
+                    ScalarStyle::SingleQuoted | ScalarStyle::DoubleQuoted => {
+                        let (quote, other) = match style {
+                            ScalarStyle::SingleQuoted => ('\'', '"'),
+                            _ => ('"', '\''),
+                        };
+
+                        // Многострочный скаляр: на этой строке только его начало
+                        if token.text.len() < 2 || !token.text.ends_with(quote) {
+                            continue;
+                        }
+
+                        let content = token.unquoted();
+                        let wrong_type = match rule.quote_type {
+                            QuoteType::Any => None,
+                            QuoteType::Single if quote != '\'' => Some("single"),
+                            QuoteType::Double if quote != '"' => Some("double"),
+                            _ => None,
+                        };
+
+                        if rule.required == QuoteRequirement::OnlyWhenNeeded
+                            && !quoted_needs_quotes(content, style, version) {
+                            format!("String value is redundantly quoted with {} quotes", quote_name(quote))
+                        } else if let Some(expected) = wrong_type.filter(|_| !content.contains(other)) {
+                            format!("String value is not quoted with {} quotes", expected)
+                        } else {
+                            continue;
+                        }
+                    }
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column,
+                    length: token.text.chars().count(),
+                    severity: rule.level.clone(),
+                    rule: "quoted-strings".to_string(),
+                    message,
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_colons(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.colons;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let mut report = |line: &Line, start: usize, length: usize, message: String| {
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: line.number,
+                column: line.text[..start].chars().count() + 1,
+                length,
+                severity: rule.level.clone(),
+                rule: "colons".to_string(),
+                message,
+                snippet: line.text.to_string(),
+            });
+        };
+
+        for line in lines {
+            for gap in line.colon_gaps() {
+                if gap.before.len() > rule.max_spaces_before {
+                    report(line, gap.before.start, gap.before.len(), "Too many spaces before colon".to_string());
+                }
+                if let Some(after) = gap.after.filter(|a| a.len() > rule.max_spaces_after) {
+                    report(line, after.start, after.len(), "Too many spaces after colon".to_string());
+                }
+            }
+
+            // `key:value` без пробела — не пара ключ-значение, а одна строка
+            let first = line.tokens.iter().find(|t| t.kind != TokenKind::SequenceEntry);
+            if let Some(token) = first.filter(|t| t.kind == TokenKind::Scalar(ScalarStyle::Plain)) {
+                if MISSING_COLON_SPACE_RE.is_match(token.text) && !token.text.contains("://") {
+                    let colon = token.offset + token.text.find(':').unwrap_or(0);
+                    report(line, colon, 1, "Missing space after colon; the line is a plain string, not a key".to_string());
+                }
+            }
+        }
+
+        results
+    }
+
+    /// `---` и `-item` сканер не считает индикаторами элемента, поэтому
+    /// проверяются только настоящие `-`, за которыми следует пробел
+    fn check_hyphens(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.hyphens;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for gap in line.hyphen_gaps() {
+                if gap.after.len() <= rule.max_spaces_after {
+                    continue;
+                }
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: gap.dash.column,
+                    length: 1,
+                    severity: rule.level.clone(),
+                    rule: "hyphens".to_string(),
+                    message: format!("Too many spaces after hyphen ({} > {})", gap.after.len(), rule.max_spaces_after),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_brackets(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        check_flow_spacing(lines, file_path, &self.config.rules.brackets, ('[', ']'), "brackets")
+    }
+
+    fn check_braces(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        check_flow_spacing(lines, file_path, &self.config.rules.braces, ('{', '}'), "braces")
+    }
+
+    fn check_commas(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.commas;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for gap in line.comma_gaps() {
+                let mut messages = vec![];
+
+                if let Some(before) = gap.before.filter(|b| b.len() > rule.max_spaces_before) {
+                    messages.push(format!("Too many spaces before comma ({} > {})", before.len(), rule.max_spaces_before));
+                }
+                if let Some(after) = gap.after {
+                    if after.len() > rule.max_spaces_after {
+                        messages.push(format!("Too many spaces after comma ({} > {})", after.len(), rule.max_spaces_after));
+                    } else if after.len() < rule.min_spaces_after {
+                        messages.push(format!("Too few spaces after comma ({} < {})", after.len(), rule.min_spaces_after));
+                    }
+                }
+
+                for message in messages {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: gap.comma.column,
+                        length: 1,
+                        severity: rule.level.clone(),
+                        rule: "commas".to_string(),
+                        message,
+                        snippet: line.text.to_string(),
+                    });
+                }
+            }
+        }
+
+        results
+    }
+
+    fn check_float_values(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.float_values;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                if token.kind != TokenKind::Scalar(ScalarStyle::Plain) {
+                    continue;
+                }
+
+                let text = token.text;
+                let message = if rule.forbid_inf && INF_RE.is_match(text) {
+                    format!("Forbidden infinite value '{}'", text)
+                } else if rule.forbid_nan && NAN_RE.is_match(text) {
+                    format!("Forbidden not-a-number value '{}'", text)
+                } else if rule.require_numeral_before_decimal && BARE_DECIMAL_RE.is_match(text) {
+                    format!("Float '{}' has no numeral before the decimal point", text)
+                } else if rule.forbid_scientific_notation && SCIENTIFIC_RE.is_match(text) {
+                    format!("Forbidden scientific notation '{}'", text)
+                } else {
+                    continue;
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column,
+                    length: text.chars().count(),
+                    severity: rule.level.clone(),
+                    rule: "float-values".to_string(),
+                    message,
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    /// В блочном маппинге значение может начаться на следующих строках:
+    /// вложенный блок глубже ключа или `- ...` на его уровне
+    fn check_empty_values(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.empty_values;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        // Строки с содержимым, без пустых и комментариев
+        let content: Vec<&Line> = lines
+            .iter()
+            .filter(|l| l.tokens.iter().any(|t| t.kind != TokenKind::Comment))
+            .collect();
+        let mut flow_depth = 0usize;
+
+        for (index, line) in content.iter().enumerate() {
+            let tokens: Vec<&Token> = line.tokens.iter().filter(|t| t.kind != TokenKind::Comment).collect();
+
+            for (i, token) in tokens.iter().enumerate() {
+                match token.kind {
+                    TokenKind::FlowStart => flow_depth += 1,
+                    TokenKind::FlowEnd => flow_depth = flow_depth.saturating_sub(1),
+                    _ => {}
+                }
+
+                if !matches!(token.kind, TokenKind::Key(_)) || tokens.get(i + 1).map(|t| t.kind) != Some(TokenKind::Colon) {
+                    continue;
+                }
+                let next = tokens.get(i + 2).map(|t| t.kind);
+
+                let message = if flow_depth > 0 {
+                    if !rule.forbid_in_flow_mappings || !matches!(next, Some(TokenKind::Comma) | Some(TokenKind::FlowEnd)) {
+                        continue;
+                    }
+                    "Empty value in flow mapping"
+                } else {
+                    if !rule.forbid_in_block_mappings || next.is_some() {
+                        continue;
+                    }
+                    let nested = content.get(index + 1).and_then(|l| l.tokens.first()).is_some_and(|first| {
+                        first.column > token.column
+                            || (first.column == token.column && first.kind == TokenKind::SequenceEntry)
+                    });
+                    if nested {
+                        continue;
+                    }
+                    "Empty value in block mapping"
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column,
+                    length: token.text.chars().count(),
+                    severity: rule.level.clone(),
+                    rule: "empty-values".to_string(),
+                    message: format!("{} for key '{}'", message, token.unquoted()),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    /// Ключ `"<<"` в кавычках — обычная строка, а не слияние
+    fn check_merge_keys(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.merge_keys;
+
+        if rule.level == Severity::Off || !rule.forbid {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                if token.kind != TokenKind::Key(ScalarStyle::Plain) || token.text != "<<" {
+                    continue;
+                }
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column,
+                    length: 2,
+                    severity: rule.level.clone(),
+                    rule: "merge-keys".to_string(),
+                    message: "Merge key '<<' is forbidden".to_string(),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_float_precision(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.float_precision;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                if token.kind != TokenKind::Scalar(ScalarStyle::Plain) {
+                    continue;
+                }
+
+                let Some(caps) = FLOAT_RE.captures(token.text) else { continue };
+                let places = caps.get(1).map_or(0, |m| m.as_str().len());
+
+                if places > rule.max_decimal_places {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: token.column,
+                        length: token.text.chars().count(),
+                        severity: rule.level.clone(),
+                        rule: "float-precision".to_string(),
+                        message: format!(
+                            "Float '{}' has {} decimal places (max {})",
+                            token.text, places, rule.max_decimal_places
+                        ),
+                        snippet: line.text.to_string(),
+                    });
+                }
+            }
+        }
+
+        results
+    }
+
+    fn check_port_range(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.port_range;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for (key, value) in line.key_values() {
+                if !rule.keys.iter().any(|k| k == key.unquoted()) {
+                    continue;
+                }
+
+                // Значение на следующих строках (вложенная структура) не проверяем
+                let Some(value) = value else { continue };
+
+                let valid = value.kind == TokenKind::Scalar(ScalarStyle::Plain)
+                    && value.text.parse::<u32>().is_ok_and(|port| (1..=65535).contains(&port));
+
+                if !valid {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: value.column,
+                        length: value.text.chars().count(),
+                        severity: rule.level.clone(),
+                        rule: "port-range".to_string(),
+                        message: format!("Port '{}' for key '{}' must be an integer in range 1-65535", value.unquoted(), key.unquoted()),
+                        snippet: line.text.to_string(),
+                    });
+                }
+            }
+        }
+
+        results
+    }
+
+    fn check_indent_sequence(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let level = &self.config.rules.indent_sequence.level;
+        let indent_sequence = self.config.format.indent_sequence;
+
+        if *level == Severity::Off {
+            return results;
+        }
+
+        for seq in scanner::key_sequences(lines) {
+            let indented = seq.dash_offset > seq.key_offset;
+
+            if indented != indent_sequence {
+                let line = &lines[seq.line_index];
+                let message = if indent_sequence {
+                    "Sequence items should be indented under their key"
+                } else {
+                    "Sequence items should be flush with their key"
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: seq.dash_offset + 1,
+                    length: 1,
+                    severity: level.clone(),
+                    rule: "indent-sequence".to_string(),
+                    message: message.to_string(),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    /// Файл должен заканчиваться содержимым, а не комментарием. Пустые строки
+    /// в конце файла проверяют new-line-at-end-of-file и empty-lines
+    fn check_final_line(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let level = &self.config.rules.final_line.level;
+
+        if *level == Severity::Off {
+            return results;
+        }
+
+        let Some(last) = lines.iter().rposition(|l| !l.text.trim().is_empty()) else {
+            return results;
+        };
+
+        // Строки блочного скаляра не содержат токенов, поэтому "# ..." внутри
+        // него комментарием не считается
+        let line = &lines[last];
+        if matches!(line.tokens.as_slice(), [token] if token.kind == TokenKind::Comment) {
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: line.number,
+                column: line.tokens[0].column,
+                length: line.tokens[0].text.chars().count(),
+                severity: level.clone(),
+                rule: "final-line".to_string(),
+                message: "File should end with content, not a comment".to_string(),
+                snippet: line.text.to_string(),
+            });
+        }
+
+        results
+    }
+
+    fn check_block_scalar_chomping(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.block_scalar_chomping;
+
+        if rule.level == Severity::Off || !rule.require_explicit {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                // Заголовок вида `|`, `>2`, `|-`, `>+1`
+                if token.kind == TokenKind::BlockScalar && !token.text.contains(['-', '+']) {
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: line.number,
+                        column: token.column,
+                        length: token.text.chars().count(),
+                        severity: rule.level.clone(),
+                        rule: "block-scalar-chomping".to_string(),
+                        message: format!(
+                            "Block scalar '{}' has no chomping indicator, use '{}-' to strip or '{}+' to keep trailing newlines",
+                            token.text, token.text, token.text
+                        ),
+                        snippet: line.text.to_string(),
+                    });
+                }
+            }
+        }
+
+        results
+    }
+
+    fn check_block_scalar_style(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.block_scalar_style;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let headers: Vec<(&Line, &Token)> = lines
+            .iter()
+            .flat_map(|line| line.tokens.iter().map(move |token| (line, token)))
+            .filter(|(_, token)| token.kind == TokenKind::BlockScalar)
+            .collect();
+
+        let style_of = |token: &Token| {
+            if token.text.starts_with('|') { BlockScalarStyle::Literal } else { BlockScalarStyle::Folded }
+        };
+
+        let preferred = match rule.prefer {
+            BlockScalarStyle::Consistent => {
+                // Побеждает большинство; при равенстве — стиль первого блока
+                let literal = headers.iter().filter(|(_, t)| style_of(t) == BlockScalarStyle::Literal).count();
+                let folded = headers.len() - literal;
+                match headers.first() {
+                    None => return results,
+                    Some(_) if literal > folded => BlockScalarStyle::Literal,
+                    Some(_) if folded > literal => BlockScalarStyle::Folded,
+                    Some((_, first)) => style_of(first),
+                }
+            }
+            ref style => style.clone(),
+        };
+
+        for (line, token) in headers {
+            if style_of(token) != preferred {
+                let (found, expected) = match preferred {
+                    BlockScalarStyle::Literal => ("folded (>)", "literal (|)"),
+                    _ => ("literal (|)", "folded (>)"),
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column,
+                    length: token.text.chars().count(),
+                    severity: rule.level.clone(),
+                    rule: "block-scalar-style".to_string(),
+                    message: format!("Block scalar uses {} style, expected {}", found, expected),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_stray_comma(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let level = &self.config.rules.stray_comma.level;
+
+        if *level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            for token in &line.tokens {
+                // Внутри flow-коллекций запятая — отдельный токен, поэтому
+                // простой скаляр, оканчивающийся на `,`, находится в блочном контексте
+                let is_plain = matches!(token.kind, TokenKind::Scalar(ScalarStyle::Plain) | TokenKind::Key(ScalarStyle::Plain));
+                if !is_plain || !token.text.ends_with(',') {
+                    continue;
+                }
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: token.column + token.text.chars().count() - 1,
+                    length: 1,
+                    severity: level.clone(),
+                    rule: "stray-comma".to_string(),
+                    message: "Trailing comma outside of a flow collection becomes part of the value".to_string(),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_inline_secrets(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.no_inline_secrets;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let patterns: Vec<String> = rule.keys.iter().map(|k| k.to_lowercase()).collect();
+
+        for line in lines {
+            for (key, value) in line.key_values() {
+                let name = key.unquoted().to_lowercase();
+                if !patterns.iter().any(|p| name.contains(p.as_str())) {
+                    continue;
+                }
+
+                // Вложенное значение (например, valueFrom) секретом не является
+                let Some(value) = value else { continue };
+                let secret = value.unquoted().trim();
+
+                if secret.is_empty() || SECRET_PLACEHOLDER_RE.is_match(secret) {
+                    continue;
+                }
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column: key.column,
+                    length: key.text.chars().count(),
+                    severity: rule.level.clone(),
+                    rule: "no-inline-secrets".to_string(),
+                    message: format!("Key '{}' contains an inline secret. Use a variable reference or secret store instead.", key.unquoted()),
+                    snippet: line.text.to_string(),
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_comments_indentation(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.comments_indentation;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        // Колонка первого значимого токена строки, если это не комментарий
+        let content_column = |line: &Line| match line.tokens.first() {
+            Some(token) if token.kind != TokenKind::Comment => Some(token.column),
+            _ => None,
+        };
+
+        let mut previous = None;
+
+        for (i, line) in lines.iter().enumerate() {
+            let comment = match line.tokens.as_slice() {
+                [token] if token.kind == TokenKind::Comment => token,
+                _ => {
+                    previous = content_column(line).or(previous);
+                    continue;
+                }
+            };
+
+            // Комментарий описывает следующий за ним ключ или блок
+            let Some(next) = lines[i + 1..].iter().find_map(content_column) else {
+                continue;
+            };
+
+            if comment.column == next || (!rule.strict && Some(comment.column) == previous) {
+                continue;
+            }
+
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: line.number,
+                column: comment.column,
+                length: comment.text.chars().count(),
+                severity: rule.level.clone(),
+                rule: "comments-indentation".to_string(),
+                message: format!("Comment should be indented like the content it precedes (column {})", next),
+                snippet: line.text.to_string(),
+            });
+        }
+
+        results
+    }
+
+    fn check_comments(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.comments;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for line in lines {
+            // Лексер не выдаёт `#` внутри кавычек и блочных скаляров за комментарий
+            let Some(comment) = line.tokens.iter().find(|t| t.kind == TokenKind::Comment) else {
+                continue;
+            };
+
+            let mut push = |column: usize, message: String| {
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: line.number,
+                    column,
+                    length: 1,
+                    severity: rule.level.clone(),
+                    rule: "comments".to_string(),
+                    message,
+                    snippet: line.text.to_string(),
+                });
+            };
+
+            if line.tokens.len() > 1 {
+                let before = &line.text[..comment.offset];
+                let spaces = before.len() - before.trim_end().len();
+                if spaces < rule.min_spaces_from_content {
+                    push(comment.column, format!(
+                        "Too few spaces before comment ({} < {})", spaces, rule.min_spaces_from_content
+                    ));
+                }
+            }
+
+            if rule.require_starting_space {
+                // `##` и `#!` в первой строке (shebang) допустимы
+                let body = comment.text.trim_start_matches('#');
+                let is_shebang = line.number == 1 && comment.column == 1 && body.starts_with('!');
+                if !body.is_empty() && !body.starts_with([' ', '\t']) && !is_shebang {
+                    let hashes = comment.text.len() - body.len();
+                    push(comment.column + hashes, "Missing starting space in comment".to_string());
+                }
+            }
+        }
+
+        results
+    }
+
+    fn check_document_start(&self, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.document_start;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let Some(first) = scanner::first_content_line(lines) else {
+            return results;
+        };
+
+        let line = &lines[first];
+        let has_marker = line.tokens[0].kind == TokenKind::DocumentStart;
+
+        let (number, message) = match (rule.present, has_marker) {
+            (true, false) => (1, "Missing document start \"---\""),
+            (false, true) => (line.number, "Found forbidden document start \"---\""),
+            _ => return results,
+        };
+
+        results.push(LintResult {
+            file: file_path.to_string(),
+            line: number,
+            column: 1,
+            length: if has_marker { 3 } else { 0 },
+            severity: rule.level.clone(),
+            rule: "document-start".to_string(),
+            message: message.to_string(),
+            snippet: lines[number - 1].text.to_string(),
+        });
+
+        results
+    }
+
+    fn check_required_fields(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.required_fields;
+        let mut required: Vec<String> = vec![];
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for pattern in self.required_paths.matching(file_path) {
+            required.extend(rule.paths[pattern].iter().cloned());
+        }
+
+        for requirement in &rule.content {
+            let selected = pointer(doc.value, &requirement.when.path)
+                .is_some_and(|value| scalar_to_string(value) == requirement.when.equals);
+            if selected {
+                required.extend(requirement.fields.iter().cloned());
+            }
+        }
+
+        // Поле, требуемое несколькими селекторами, проверяем один раз
+        required.sort();
+        required.dedup();
+
+        self.check_required_in_value(doc, &required, file_path, &mut results);
+        results
+    }
+
+    fn check_required_in_value(&self, doc: &Document, required_fields: &[String],
+                               file_path: &str, results: &mut Vec<LintResult>) {
+        if let Value::Mapping(mapping) = doc.value {
+            for field in required_fields {
+                let parts: Vec<&str> = field.split('.').collect();
+                self.check_nested_field(doc, mapping, &parts, &mut vec![], file_path, results);
+            }
+        }
+    }
+
+    fn check_nested_field(&self, doc: &Document, mapping: &Mapping, parts: &[&str],
+                          path: &mut Vec<PathSegment>, file_path: &str, results: &mut Vec<LintResult>) {
+        if parts.is_empty() {
+            return;
+        }
+
+        let key = parts[0];
+        let key_value = Value::String(key.to_string());
+
+        if !mapping.contains_key(&key_value) {
+            // Отсутствующее поле указываем на родительском ключе
+            let location = doc.locate_key(path);
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: location.line,
+                column: location.column,
+                length: location.length,
+                severity: self.config.rules.required_fields.level.clone(),
+                rule: "required-fields".to_string(),
+                message: format!("Missing required field: {}", key),
+                snippet: location.snippet,
+            });
+            return;
+        }
+
+        if parts.len() > 1 {
+            if let Some(Value::Mapping(sub_mapping)) = mapping.get(&key_value) {
+                path.push(PathSegment::Key(key.to_string()));
+                self.check_nested_field(doc, sub_mapping, &parts[1..], path, file_path, results);
+                path.pop();
+            }
+        }
+    }
+
+    fn check_environment_markers(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.environment_markers;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        for pattern in self.environment_files.matching(file_path) {
+            let markers = &rule.files[pattern];
+            let mut keys: Vec<&String> = markers.keys().collect();
+            keys.sort();
+
+            for key in keys {
+                let expected = &markers[key];
+                let actual = key
+                    .split('.')
+                    .try_fold(doc.value, |v, part| v.get(part))
+                    .map(scalar_to_string);
+
+                if actual.as_deref() == Some(expected.as_str()) {
+                    continue;
+                }
+
+                let path: Vec<PathSegment> = key.split('.').map(|p| PathSegment::Key(p.to_string())).collect();
+                let location = doc.locate_value(&path);
+
+                let message = match actual {
+                    Some(actual) => format!(
+                        "Files matching '{}' must have '{}: {}', found '{}'",
+                        pattern, key, expected, actual
+                    ),
+                    None => format!("Files matching '{}' must have '{}: {}'", pattern, key, expected),
+                };
+
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: location.line,
+                    column: location.column,
+                    length: location.length,
+                    severity: rule.level.clone(),
+                    rule: "environment-markers".to_string(),
+                    message,
+                    snippet: location.snippet,
+                });
+            }
+        }
+
+        results
+    }
+
+    fn check_homogeneous_values(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+
+        if self.config.rules.homogeneous_values.level != Severity::Off {
+            self.visit_mixed_mappings(doc, doc.value, &mut vec![], file_path, &mut results);
+        }
+
+        results
+    }
+
+    fn visit_mixed_mappings(&self, doc: &Document, value: &Value, path: &mut Vec<PathSegment>,
+                            file_path: &str, results: &mut Vec<LintResult>) {
+        let rule = &self.config.rules.homogeneous_values;
+
+        match value {
+            Value::Mapping(mapping) => {
+                let collections = mapping
+                    .values()
+                    .filter(|v| matches!(v, Value::Mapping(_) | Value::Sequence(_)))
+                    .count();
+                let scalars = mapping.len() - collections;
+                let ratio = scalars.min(collections) as f64 / mapping.len().max(1) as f64;
+
+                if ratio > rule.max_mixed_ratio {
+                    let name = if path.is_empty() { "<root>".to_string() } else { format_path(path) };
+                    let location = doc.locate_key(path);
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: location.line,
+                        column: location.column,
+                        length: location.length,
+                        severity: rule.level.clone(),
+                        rule: "homogeneous-values".to_string(),
+                        message: format!(
+                            "Mapping '{}' mixes {} scalar and {} collection values. Consider grouping them.",
+                            name, scalars, collections
+                        ),
+                        snippet: location.snippet,
+                    });
+                }
+
+                for (k, v) in mapping {
+                    path.push(PathSegment::Key(scalar_to_string(k)));
+                    self.visit_mixed_mappings(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            Value::Sequence(seq) => {
+                for (i, v) in seq.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    self.visit_mixed_mappings(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn check_duplicate_blocks(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.duplicate_blocks;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let mut blocks: HashMap<&Value, Vec<Vec<PathSegment>>> = HashMap::new();
+        collect_blocks(doc, doc.value, &mut vec![], rule.min_size, &mut blocks);
+
+        // Сначала крупные блоки: повторы внутри уже найденных копий не сообщаем
+        let mut groups: Vec<_> = blocks.into_values().filter(|paths| paths.len() > 1).collect();
+        groups.sort_by_key(|paths| (paths[0].len(), doc.locate_key(&paths[0]).line));
+
+        let mut reported: Vec<Vec<PathSegment>> = vec![];
+
+        for paths in groups {
+            if paths.iter().all(|p| reported.iter().any(|r| p.starts_with(r))) {
+                continue;
+            }
+
+            let locations: Vec<_> = paths.iter().map(|p| doc.locate_key(p)).collect();
+            let lines: Vec<String> = locations.iter().map(|l| l.line.to_string()).collect();
+
+            for (path, location) in paths.iter().zip(locations) {
+                results.push(LintResult {
+                    file: file_path.to_string(),
+                    line: location.line,
+                    column: location.column,
+                    length: location.length,
+                    severity: rule.level.clone(),
+                    rule: "duplicate-blocks".to_string(),
+                    message: format!(
+                        "Block '{}' is repeated {} times (lines {}). Consider extracting it into an anchor.",
+                        format_path(path), paths.len(), lines.join(", ")
+                    ),
+                    snippet: location.snippet,
+                });
+            }
+
+            reported.extend(paths);
+        }
+
+        results.sort_by_key(|r| (r.line, r.column));
+        results
+    }
+
+    fn check_key_ordering(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+
+        if self.config.rules.key_ordering.level != Severity::Off {
+            self.visit_key_order(doc, doc.value, &mut vec![], file_path, &mut results);
+        }
+
+        results
+    }
+
+    fn visit_key_order(&self, doc: &Document, value: &Value, path: &mut Vec<PathSegment>,
+                       file_path: &str, results: &mut Vec<LintResult>) {
+        let rule = &self.config.rules.key_ordering;
+
+        match value {
+            Value::Mapping(mapping) => {
+                let sort_key = |key: &str| if rule.ignore_case { key.to_lowercase() } else { key.to_string() };
+                let mut previous: Option<String> = None;
+
+                for (k, v) in mapping {
+                    let key = scalar_to_string(k);
+                    path.push(PathSegment::Key(key.clone()));
+
+                    if let Some(prev) = &previous {
+                        if sort_key(&key) < sort_key(prev) {
+                            let location = doc.locate_key(path);
+                            results.push(LintResult {
+                                file: file_path.to_string(),
+                                line: location.line,
+                                column: location.column,
+                                length: location.length,
+                                severity: rule.level.clone(),
+                                rule: "key-ordering".to_string(),
+                                message: format!("Wrong ordering of key '{}': it should come before '{}'", key, prev),
+                                snippet: location.snippet,
+                            });
+                        }
+                    }
+
+                    self.visit_key_order(doc, v, path, file_path, results);
+                    path.pop();
+
+                    // Сравниваем со старшим из уже встреченных ключей, чтобы
+                    // один ключ не в своём месте не порождал каскад нарушений
+                    if previous.as_ref().is_none_or(|prev| sort_key(&key) >= sort_key(prev)) {
+                        previous = Some(key);
+                    }
+                }
+            }
+
+            Value::Sequence(seq) => {
+                for (i, v) in seq.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    self.visit_key_order(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn check_key_order_template(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.key_order_template;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let Value::Mapping(mapping) = doc.value else {
+            return results;
+        };
+
+        // Самый поздний по шаблону ключ из уже встреченных
+        let mut latest: Option<(usize, String)> = None;
+
+        for k in mapping.keys() {
+            let key = scalar_to_string(k);
+            let Some(rank) = rule.order.iter().position(|name| *name == key) else { continue };
+
+            match &latest {
+                Some((latest_rank, previous)) if rank < *latest_rank => {
+                    let location = doc.locate_key(&[PathSegment::Key(key.clone())]);
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: location.line,
+                        column: location.column,
+                        length: location.length,
+                        severity: rule.level.clone(),
+                        rule: "key-order-template".to_string(),
+                        message: format!("Key '{}' should come before '{}' (expected order: {})",
+                                         key, previous, rule.order.join(", ")),
+                        snippet: location.snippet,
+                    });
+                    break;
+                }
+                _ => latest = Some((rank, key)),
+            }
+        }
+
+        results
+    }
+
+    fn check_forbidden_keys(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.forbidden_keys;
+
+        if rule.level == Severity::Off || rule.keys.is_empty() {
+            return results;
+        }
+
+        let mut paths = vec![];
+        collect_key_paths(doc.value, &mut vec![], &mut paths);
+
+        for path in &paths {
+            let Some(PathSegment::Key(key)) = path.last() else { continue };
+            if !self.forbidden_keys.is_match(key) {
+                continue;
+            }
+
+            let location = doc.locate_key(path);
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: location.line,
+                column: location.column,
+                length: location.length,
+                severity: rule.level.clone(),
+                rule: "forbidden-keys".to_string(),
+                message: format!("Forbidden key '{}'", format_path(path)),
+                snippet: location.snippet,
+            });
+        }
+
+        results
+    }
+
+    /// Ключи без букв (`200`, `<<`) соглашению не подчиняются и пропускаются
+    fn check_key_naming(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.key_naming;
+
+        if rule.level == Severity::Off {
+            return results;
+        }
+
+        let (pattern, name): (&Regex, _) = match rule.convention {
+            NamingConvention::Snake => (&SNAKE_CASE_RE, "snake_case"),
+            NamingConvention::Camel => (&CAMEL_CASE_RE, "camelCase"),
+            NamingConvention::Kebab => (&KEBAB_CASE_RE, "kebab-case"),
+            NamingConvention::Pascal => (&PASCAL_CASE_RE, "PascalCase"),
+        };
+
+        let mut paths = vec![];
+        collect_key_paths(doc.value, &mut vec![], &mut paths);
+
+        for path in &paths {
+            let Some(PathSegment::Key(key)) = path.last() else { continue };
+            if pattern.is_match(key) || self.naming_ignored.is_match(key) || !key.chars().any(|c| c.is_ascii_alphabetic()) {
+                continue;
+            }
+
+            let location = doc.locate_key(path);
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: location.line,
+                column: location.column,
+                length: location.length,
+                severity: rule.level.clone(),
+                rule: "key-naming".to_string(),
+                message: format!("Key '{}' is not {}", key, name),
+                snippet: location.snippet,
+            });
+        }
+
+        results
+    }
+
+    fn check_key_occurrence_limit(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.key_occurrence_limit;
+
+        if rule.level == Severity::Off || rule.limits.is_empty() {
+            return results;
+        }
+
+        let mut paths = vec![];
+        collect_key_paths(doc.value, &mut vec![], &mut paths);
+
+        let mut occurrences: BTreeMap<&str, Vec<&Vec<PathSegment>>> = BTreeMap::new();
+        for path in &paths {
+            for pattern in self.occurrence_limits.matching(&key_glob_path(path)) {
+                occurrences.entry(pattern).or_default().push(path);
+            }
+        }
+
+        for (pattern, matched) in occurrences {
+            let limit = &rule.limits[pattern];
+            if matched.len() <= *limit {
+                continue;
+            }
+
+            let lines: Vec<String> = matched.iter().map(|p| doc.locate_key(p).line.to_string()).collect();
+            let location = doc.locate_key(matched[*limit]);
+
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: location.line,
+                column: location.column,
+                length: location.length,
+                severity: rule.level.clone(),
+                rule: "key-occurrence-limit".to_string(),
+                message: format!(
+                    "Key '{}' occurs {} times (limit {}) at lines {}",
+                    pattern, matched.len(), limit, lines.join(", ")
+                ),
+                snippet: location.snippet,
+            });
+        }
+
+        results
+    }
+
+    fn check_duplicate_named_items(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let rule = &self.config.rules.duplicate_named_items;
+
+        if rule.level == Severity::Off || rule.sequences.is_empty() {
+            return results;
+        }
+
+        let mut paths = vec![];
+        collect_key_paths(doc.value, &mut vec![], &mut paths);
+
+        for path in paths.iter().filter(|path| self.named_sequences.is_match(key_glob_path(path))) {
+            let Some(Value::Sequence(items)) = value_at(doc.value, path) else { continue };
+            let mut seen: HashMap<String, usize> = HashMap::new();
+
+            for (i, item) in items.iter().enumerate() {
+                let Some(name) = item.get(rule.field.as_str()).filter(|v| !v.is_mapping() && !v.is_sequence()) else {
+                    continue;
+                };
+                let name = scalar_to_string(name);
+
+                let mut item_path = path.clone();
+                item_path.push(PathSegment::Index(i));
+                item_path.push(PathSegment::Key(rule.field.clone()));
+                let location = doc.locate_value(&item_path);
+
+                match seen.get(&name) {
+                    Some(first) => results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: location.line,
+                        column: location.column,
+                        length: location.length,
+                        severity: rule.level.clone(),
+                        rule: "duplicate-named-items".to_string(),
+                        message: format!(
+                            "Duplicate {} '{}' in '{}' (first defined at line {})",
+                            rule.field, name, format_path(path), first
+                        ),
+                        snippet: location.snippet,
+                    }),
+                    None => {
+                        seen.insert(name, location.line);
+                    }
+                }
+            }
+        }
+
+        results.sort_by_key(|r| (r.line, r.column));
+        results
+    }
+
+    fn check_max_depth(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+
+        if self.config.rules.max_depth.level != Severity::Off {
+            self.visit_depth(doc, doc.value, &mut vec![], file_path, &mut results);
+        }
+
+        results
+    }
+
+    /// Сообщает о первом узле ветки, превысившем предел; глубже не спускается,
+    /// а в сообщении указывает самую глубокую точку этой ветки
+    fn visit_depth(&self, doc: &Document, value: &Value, path: &mut Vec<PathSegment>,
+                   file_path: &str, results: &mut Vec<LintResult>) {
+        let rule = &self.config.rules.max_depth;
+
+        if path.len() > rule.max {
+            let mut deepest = path.clone();
+            deepest_path(value, path, &mut deepest);
+
+            let location = doc.locate_key(path);
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: location.line,
+                column: location.column,
+                length: location.length,
+                severity: rule.level.clone(),
+                rule: "max-depth".to_string(),
+                message: format!(
+                    "Nesting depth {} exceeds the maximum of {} (deepest at '{}')",
+                    deepest.len(), rule.max, format_path(&deepest)
+                ),
+                snippet: location.snippet,
+            });
+            return;
+        }
+
+        match value {
+            Value::Mapping(mapping) => {
+                for (k, v) in mapping {
+                    path.push(PathSegment::Key(scalar_to_string(k)));
+                    self.visit_depth(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            Value::Sequence(seq) => {
+                for (i, v) in seq.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    self.visit_depth(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            Value::Tagged(tagged) => self.visit_depth(doc, &tagged.value, path, file_path, results),
+
+            _ => {}
+        }
+    }
+
+    fn check_compare_to_defaults(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+
+        if let Some(defaults) = &self.defaults {
+            self.visit_defaults_diff(doc, doc.value, defaults, &mut vec![], file_path, &mut results);
+        }
+
+        results
+    }
+
+    fn visit_defaults_diff(&self, doc: &Document, value: &Value, default: &Value, path: &mut Vec<PathSegment>,
+                           file_path: &str, results: &mut Vec<LintResult>) {
+        let rule = &self.config.rules.compare_to_defaults;
+        let name = |path: &[PathSegment]| if path.is_empty() { "<root>".to_string() } else { format_path(path) };
+
+        let result = |location: Location, message: String| LintResult {
+            file: file_path.to_string(),
+            line: location.line,
+            column: location.column,
+            length: location.length,
+            severity: rule.level.clone(),
+            rule: "compare-to-defaults".to_string(),
+            message,
+            snippet: location.snippet,
+        };
+
+        match (value, default) {
+            (Value::Mapping(mapping), Value::Mapping(defaults)) => {
+                for (k, v) in mapping {
+                    path.push(PathSegment::Key(scalar_to_string(k)));
+                    match defaults.get(k) {
+                        Some(d) => self.visit_defaults_diff(doc, v, d, path, file_path, results),
+                        None if rule.report_extra => results.push(result(
+                            doc.locate_key(path),
+                            format!("Key '{}' is not present in defaults", name(path)),
+                        )),
+                        None => {}
+                    }
+                    path.pop();
+                }
+
+                if rule.report_missing {
+                    for k in defaults.keys().filter(|k| !mapping.contains_key(*k)) {
+                        let location = doc.locate_key(path);
+                        path.push(PathSegment::Key(scalar_to_string(k)));
+                        results.push(result(location, format!("Key '{}' from defaults is missing", name(path))));
+                        path.pop();
+                    }
+                }
+            }
+
+            (Value::Sequence(seq), Value::Sequence(defaults)) if seq.len() == defaults.len() => {
+                for (i, (v, d)) in seq.iter().zip(defaults).enumerate() {
+                    path.push(PathSegment::Index(i));
+                    self.visit_defaults_diff(doc, v, d, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            (Value::Tagged(tagged), _) => self.visit_defaults_diff(doc, &tagged.value, default, path, file_path, results),
+
+            _ if value != default => results.push(result(
+                doc.locate_value(path),
+                format!(
+                    "Value of '{}' differs from defaults: {} (default {})",
+                    name(path), describe_value(value), describe_value(default)
+                ),
+            )),
+
+            _ => {}
+        }
+    }
+
+    fn check_value_types(&self, doc: &Document, file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+
+        if self.config.rules.value_types.level != Severity::Off {
+            self.visit_value(doc, doc.value, &mut vec![], file_path, &mut results);
+        }
+
+        results
+    }
+
+    fn visit_value(&self, doc: &Document, value: &Value, path: &mut Vec<PathSegment>,
+                   file_path: &str, results: &mut Vec<LintResult>) {
+        match value {
+            Value::String(s) => {
+                // Проверка на boolean строки в кавычках; без кавычек это забота правила truthy
+                if self.config.rules.value_types.check_bool_values && doc.is_quoted(path) {
+                    let lower = s.to_lowercase();
+                    if lower == "true" || lower == "false" || lower == "yes" || lower == "no" {
+                        let location = doc.locate_value(path);
+                        results.push(LintResult {
+                            file: file_path.to_string(),
+                            line: location.line,
+                            column: location.column,
+                            length: location.length,
+                            severity: self.config.rules.value_types.level.clone(),
+                            rule: "value-types".to_string(),
+                            message: format!("Boolean-like string: '{}'. Consider using boolean type.", s),
+                            snippet: location.snippet,
+                        });
+                    }
+                }
+
+                // Проверка на числовые строки. Кавычки вокруг `01234` и `0755` нужны,
+                // чтобы сохранить нули, — этого требуют leading-zeros и octal-values
+                if self.config.rules.value_types.strict_numbers
+                    && !LEADING_ZEROS_RE.is_match(s)
+                    && (s.parse::<i64>().is_ok() || s.parse::<f64>().is_ok())
+                {
+                    let location = doc.locate_value(path);
+                    results.push(LintResult {
+                        file: file_path.to_string(),
+                        line: location.line,
+                        column: location.column,
+                        length: location.length,
+                        severity: self.config.rules.value_types.level.clone(),
+                        rule: "value-types".to_string(),
+                        message: format!("Number-like string: '{}'. Consider using number type.", s),
+                        snippet: location.snippet,
+                    });
+                }
+            }
+
+            Value::Mapping(mapping) => {
+                for (k, v) in mapping {
+                    path.push(PathSegment::Key(scalar_to_string(k)));
+                    self.visit_value(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            Value::Sequence(seq) => {
+                for (i, v) in seq.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    self.visit_value(doc, v, path, file_path, results);
+                    path.pop();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn check_duplicates(&self, index: &PathIndex, lines: &[Line], file_path: &str) -> Vec<LintResult> {
+        let mut results = vec![];
+        let level = &self.config.rules.duplicates.level;
+
+        if *level == Severity::Off {
+            return results;
+        }
+
+        for node in index.duplicate_keys() {
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: node.key.line,
+                column: node.key.column,
+                length: token_length(lines, node.key),
+                severity: level.clone(),
+                rule: "duplicates".to_string(),
+                message: format!("Duplicate key: '{}'", format_path(&node.path)),
+                snippet: lines.get(node.key.line - 1).map_or("", |l| l.text).to_string(),
+            });
+        }
+
+        results
+    }
+}
+
+/// Разобранный документ вместе с индексом позиций его узлов
+struct Document<'a> {
+    value: &'a Value,
+    /// Номер документа в потоке, с 0
+    number: usize,
+    index: &'a PathIndex,
+    lines: &'a [Line<'a>],
+}
+
+/// Место нарушения, найденное по пути в дереве
+struct Location {
+    line: usize,
+    column: usize,
+    length: usize,
+    snippet: String,
+}
+
+impl Document<'_> {
+    fn locate(&self, position: Option<Position>) -> Location {
+        // Узел не найден — указываем на начало документа
+        match position.or_else(|| self.index.first_position(self.number)) {
+            Some(p) => Location {
+                line: p.line,
+                column: p.column,
+                length: token_length(self.lines, p),
+                snippet: self.lines.get(p.line - 1).map_or("", |l| l.text).to_string(),
+            },
+            None => Location { line: 1, column: 1, length: 0, snippet: "".to_string() },
+        }
+    }
+
+    /// Токен значения узла, если оно записано на строке ключа
+    fn value_token(&self, path: &[PathSegment]) -> Option<&Token<'_>> {
+        let position = self.index.node(self.number, path)?.value?;
+
+        self.lines
+            .get(position.line - 1)?
+            .tokens
+            .iter()
+            .find(|t| t.column == position.column)
+    }
+
+    /// Значение узла записано в тексте ссылкой на якорь
+    fn is_alias(&self, path: &[PathSegment]) -> bool {
+        self.value_token(path).is_some_and(|t| t.kind == TokenKind::Alias)
+    }
+
+    fn is_quoted(&self, path: &[PathSegment]) -> bool {
+        self.value_token(path).is_some_and(|t| {
+            matches!(t.kind, TokenKind::Scalar(ScalarStyle::SingleQuoted | ScalarStyle::DoubleQuoted))
+        })
+    }
+
+    fn locate_key(&self, path: &[PathSegment]) -> Location {
+        self.locate(self.index.key_position(self.number, path))
+    }
+
+    fn locate_value(&self, path: &[PathSegment]) -> Location {
+        self.locate(self.index.value_position(self.number, path))
+    }
+}
+
+/// Собирает коллекции не меньше `min_size` узлов, группируя одинаковые.
+/// Возвращает размер поддерева в узлах
+fn collect_blocks<'a>(doc: &Document, value: &'a Value, path: &mut Vec<PathSegment>, min_size: usize,
+                      blocks: &mut HashMap<&'a Value, Vec<Vec<PathSegment>>>) -> usize {
+    let size = match value {
+        Value::Mapping(mapping) => {
+            let mut size = 1;
+            for (k, v) in mapping {
+                path.push(PathSegment::Key(scalar_to_string(k)));
+                size += 1 + collect_blocks(doc, v, path, min_size, blocks);
+                path.pop();
+            }
+            size
+        }
+
+        Value::Sequence(seq) => {
+            let mut size = 1;
+            for (i, v) in seq.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                size += collect_blocks(doc, v, path, min_size, blocks);
+                path.pop();
+            }
+            size
+        }
+
+        Value::Tagged(tagged) => collect_blocks(doc, &tagged.value, path, min_size, blocks),
+
+        _ => return 1,
+    };
+
+    // Ссылка `*alias` раскрывается в копию якоря — это не повтор в тексте
+    if size >= min_size && !path.is_empty() && !doc.is_alias(path) {
+        blocks.entry(value).or_default().push(path.clone());
+    }
+
+    size
+}
+
+/// Пути всех ключей дерева в порядке обхода
+fn collect_key_paths(value: &Value, path: &mut Vec<PathSegment>, paths: &mut Vec<Vec<PathSegment>>) {
+    match value {
+        Value::Mapping(mapping) => {
+            for (k, v) in mapping {
+                path.push(PathSegment::Key(scalar_to_string(k)));
+                paths.push(path.clone());
+                collect_key_paths(v, path, paths);
+                path.pop();
+            }
+        }
+
+        Value::Sequence(seq) => {
+            for (i, v) in seq.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                collect_key_paths(v, path, paths);
+                path.pop();
+            }
+        }
+
+        Value::Tagged(tagged) => collect_key_paths(&tagged.value, path, paths),
+
+        _ => {}
+    }
+}
+
+/// Самый длинный путь поддерева `value`, начинающегося в `path`
+fn deepest_path(value: &Value, path: &mut Vec<PathSegment>, deepest: &mut Vec<PathSegment>) {
+    if path.len() > deepest.len() {
+        *deepest = path.clone();
+    }
+
+    match value {
+        Value::Mapping(mapping) => {
+            for (k, v) in mapping {
+                path.push(PathSegment::Key(scalar_to_string(k)));
+                deepest_path(v, path, deepest);
+                path.pop();
+            }
+        }
+
+        Value::Sequence(seq) => {
+            for (i, v) in seq.iter().enumerate() {
+                path.push(PathSegment::Index(i));
+                deepest_path(v, path, deepest);
+                path.pop();
+            }
+        }
+
+        Value::Tagged(tagged) => deepest_path(&tagged.value, path, deepest),
+
+        _ => {}
+    }
+}
+
+/// Значение для сообщения: скаляр как есть, коллекция — её размер
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Mapping(mapping) => format!("mapping of {} keys", mapping.len()),
+        Value::Sequence(seq) => format!("sequence of {} items", seq.len()),
+        Value::Tagged(tagged) => describe_value(&tagged.value),
+        Value::String(s) => format!("'{}'", s),
+        other => scalar_to_string(other),
+    }
+}
+
+/// Компилирует шаблоны из настройки правила; неверный шаблон — ошибка конфигурации
+fn glob_set<'a>(rule: &str, patterns: impl IntoIterator<Item = &'a String>,
+                glob: impl Fn(&str) -> Result<Glob, globset::Error>) -> anyhow::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(glob(pattern).with_context(|| format!("invalid pattern '{}' for rule {}", pattern, rule))?);
+    }
+    builder.build().with_context(|| format!("failed to compile patterns of rule {}", rule))
+}
+
+/// Скомпилированные шаблоны и их исходный текст: индекс совпадения в наборе —
+/// индекс шаблона в отсортированном списке
+struct PatternSet {
+    set: GlobSet,
+    patterns: Vec<String>,
+}
+
+impl PatternSet {
+    fn new<'a>(rule: &str, patterns: impl IntoIterator<Item = &'a String>,
+               glob: impl Fn(&str) -> Result<Glob, globset::Error>) -> anyhow::Result<Self> {
+        let mut patterns: Vec<String> = patterns.into_iter().cloned().collect();
+        patterns.sort();
+        let set = glob_set(rule, &patterns, glob)?;
+        Ok(PatternSet { set, patterns })
+    }
+
+    /// Шаблоны, совпавшие с путём, в порядке сортировки
+    fn matching<'s>(&'s self, path: &str) -> impl Iterator<Item = &'s str> {
+        let mut matches = self.set.matches(path);
+        matches.sort_unstable();
+        matches.into_iter().map(|i| self.patterns[i].as_str())
+    }
+}
+
+/// Ключи пути через `/` без индексов последовательностей, для сопоставления
+/// с шаблонами вида `spec.**.image`, записанными через точку
+fn key_glob_path(path: &[PathSegment]) -> String {
+    let keys: Vec<&str> = path
+        .iter()
+        .filter_map(|s| match s {
+            PathSegment::Key(key) => Some(key.as_str()),
+            PathSegment::Index(_) => None,
+        })
+        .collect();
+    keys.join("/")
+}
+
+/// Значение по пути из сегментов
+fn value_at<'v>(value: &'v Value, path: &[PathSegment]) -> Option<&'v Value> {
+    path.iter().try_fold(value, |current, segment| match (current, segment) {
+        (Value::Mapping(mapping), PathSegment::Key(key)) => mapping.get(key.as_str()),
+        (Value::Sequence(seq), PathSegment::Index(i)) => seq.get(*i),
+        _ => None,
+    })
+}
+
+/// Длина токена, начинающегося в позиции, в символах
+fn token_length(lines: &[Line], position: Position) -> usize {
+    lines
+        .get(position.line - 1)
+        .and_then(|line| line.tokens.iter().find(|t| t.column == position.column))
+        .map_or(0, |t| t.text.chars().count())
+}
+
+/// Пробелы и табуляция в начале строки
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Значение по пути вида `/spec/containers/0/image`
+fn pointer<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('/')
+        .filter(|part| !part.is_empty())
+        .try_fold(value, |current, part| match current {
+            Value::Mapping(mapping) => mapping.get(part),
+            Value::Sequence(seq) => part.parse::<usize>().ok().and_then(|i| seq.get(i)),
+            _ => None,
+        })
+}
+
+/// Строковое представление значения для сравнения с ожидаемым
+fn scalar_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => "null".to_string(),
+        other => serde_yaml::to_string(other).unwrap_or_default().trim_end().to_string(),
+    }
+}
+
+/// Простой скаляр читается как та же самая строка, а не число, булево,
+/// null, дата или структура
+fn is_plain_string(text: &str, version: YamlVersion) -> bool {
+    if version == YamlVersion::V1_1 && (TRUTHY_VALUES.contains(&text) || TIMESTAMP_RE.is_match(text)) {
+        return false;
+    }
+
+    matches!(serde_yaml::from_str::<Value>(text), Ok(Value::String(s)) if s == text)
+}
+
+/// Без кавычек значение изменит тип или смысл. Символы flow-коллекций
+/// считаются опасными всегда: контекст по токену не известен
+fn quoted_needs_quotes(content: &str, style: ScalarStyle, version: YamlVersion) -> bool {
+    let value = match style {
+        ScalarStyle::SingleQuoted => content.replace("''", "'"),
+        // Экранированные последовательности есть только в двойных кавычках
+        _ if content.contains('\\') => return true,
+        _ => content.to_string(),
+    };
+
+    value.is_empty() || value.contains([',', '[', ']', '{', '}']) || !is_plain_string(&value, version)
+}
+
+fn quote_name(quote: char) -> &'static str {
+    if quote == '"' { "double" } else { "single" }
+}
+
+/// Пробелы внутри flow-коллекции на одной строке с разделителем. Пустые
+/// коллекции и разделители в конце или начале строки не проверяются;
+/// скобки внутри строк в кавычках сканер токенами не считает
+fn check_flow_spacing(lines: &[Line], file_path: &str, rule: &BracketsRule, delimiters: (char, char),
+                      name: &str) -> Vec<LintResult> {
+    let mut results = vec![];
+
+    if rule.level == Severity::Off {
+        return results;
+    }
+
+    let (open, close) = (delimiters.0.to_string(), delimiters.1.to_string());
+
+    for line in lines {
+        for pair in line.tokens.windows(2) {
+            let (delimiter, spaces) = match (pair[0].kind, pair[1].kind) {
+                (TokenKind::FlowStart, TokenKind::FlowEnd) => continue,
+                (TokenKind::FlowStart, kind) if pair[0].text == open && kind != TokenKind::Comment => {
+                    (&pair[0], pair[1].offset - pair[0].offset - 1)
+                }
+                (_, TokenKind::FlowEnd) if pair[1].text == close => {
+                    (&pair[1], pair[1].offset - pair[0].offset - pair[0].text.len())
+                }
+                _ => continue,
+            };
+
+            let message = if spaces > rule.max_spaces_inside {
+                format!("Too many spaces inside {} ({} > {})", name, spaces, rule.max_spaces_inside)
+            } else if spaces < rule.min_spaces_inside {
+                format!("Too few spaces inside {} ({} < {})", name, spaces, rule.min_spaces_inside)
+            } else {
+                continue;
+            };
+
+            results.push(LintResult {
+                file: file_path.to_string(),
+                line: line.number,
+                column: delimiter.column,
+                length: 1,
+                severity: rule.level.clone(),
+                rule: name.to_string(),
+                message,
+                snippet: line.text.to_string(),
+            });
+        }
+    }
+
+    results
+}
+
+type AnchorUse<'l, 'a> = (&'l Line<'a>, &'l Token<'a>, bool);
+type AnchorViolation<'l, 'a> = (&'l Line<'a>, &'l Token<'a>, String);
+
+/// Конец документа: неиспользованные якоря становятся нарушениями, объявления забываются
+fn close_anchor_scope<'l, 'a>(declared: &mut Vec<AnchorUse<'l, 'a>>, violations: &mut Vec<AnchorViolation<'l, 'a>>,
+                              forbid_unused: bool) {
+    for (line, token, used) in declared.drain(..) {
+        if forbid_unused && !used {
+            violations.push((line, token, format!("Anchor '{}' is never used", &token.text[1..])));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Находки одного правила
+    fn lint(config: Config, content: &str, rule: &str) -> Vec<LintResult> {
+        lint_named(config, "test.yaml", content, rule)
+    }
+
+    fn lint_named(config: Config, file_path: &str, content: &str, rule: &str) -> Vec<LintResult> {
+        let mut results = RuleChecker::new(config).unwrap().check_file(content, file_path);
+        results.retain(|r| r.rule == rule);
+        results
+    }
+
+    #[test]
+    fn invalid_forbidden_key_pattern_is_a_config_error() {
+        let mut config = Config::default();
+        config.rules.forbidden_keys.keys = vec!["api_[key".to_string()];
+
+        let error = RuleChecker::new(config).err().expect("pattern is rejected");
+        assert!(error.to_string().contains("'api_[key'"), "{:#}", error);
+    }
+
+    #[test]
+    fn invalid_required_fields_pattern_is_a_config_error() {
+        let mut config = Config::default();
+        config.rules.required_fields.paths.insert("deploy/{a,b".to_string(), vec!["kind".to_string()]);
+
+        let error = RuleChecker::new(config).err().expect("pattern is rejected");
+        assert!(error.to_string().contains("'deploy/{a,b'"), "{:#}", error);
+    }
+
+    #[test]
+    fn invalid_key_naming_ignore_pattern_is_a_config_error() {
+        let mut config = Config::default();
+        config.rules.key_naming.ignore = vec!["x-[".to_string()];
+
+        let error = RuleChecker::new(config).err().expect("pattern is rejected");
+        assert!(error.to_string().contains("'x-['"), "{:#}", error);
+    }
+
+    #[test]
+    fn invalid_key_occurrence_pattern_is_a_config_error() {
+        let mut config = Config::default();
+        config.rules.key_occurrence_limit.limits.insert("spec.[".to_string(), 1);
+
+        let error = RuleChecker::new(config).err().expect("pattern is rejected");
+        assert!(error.to_string().contains("'spec.['"), "{:#}", error);
+    }
+
+    #[test]
+    fn invalid_duplicate_named_items_pattern_is_a_config_error() {
+        let mut config = Config::default();
+        config.rules.duplicate_named_items.sequences.push("**.{env".to_string());
+
+        let error = RuleChecker::new(config).err().expect("pattern is rejected");
+        assert!(error.to_string().contains("'**.{env'"), "{:#}", error);
+    }
+
+    #[test]
+    fn invalid_environment_markers_pattern_is_a_config_error() {
+        let mut config = Config::default();
+        config.rules.environment_markers.files.insert("*.prod.[yaml".to_string(), HashMap::new());
+
+        let error = RuleChecker::new(config).err().expect("pattern is rejected");
+        assert!(error.to_string().contains("'*.prod.[yaml'"), "{:#}", error);
+    }
+
+    #[test]
+    fn implicit_dates_flags_unquoted_dates_and_timestamps() {
+        for content in ["date: 2023-01-01\n", "at: 2023-01-01T10:00:00Z\n"] {
+            let results = lint(Config::default(), content, "implicit-dates");
+            assert_eq!(results.len(), 1, "{}", content);
+            assert_eq!(results[0].column, content.find('2').unwrap() + 1);
+        }
+
+        assert!(lint(Config::default(), "date: \"2023-01-01\"\n", "implicit-dates").is_empty());
+    }
+
+    #[test]
+    fn trailing_spaces_column_counts_characters() {
+        let results = lint(Config::default(), "name: привет   \nok: yes\nemoji: 🚀 \t \n", "trailing-spaces");
+
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].line, results[0].column, results[0].length), (1, 13, 3));
+        assert_eq!((results[1].line, results[1].column, results[1].length), (3, 9, 3));
+    }
+
+    #[test]
+    fn port_range_accepts_only_valid_ports() {
+        let results = lint(Config::default(), "a:\n  port: 0\nb:\n  port: 70000\nc:\n  port: 8080\n", "port-range");
+
+        let lines: Vec<usize> = results.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+        assert_eq!(results[0].column, 9);
+    }
+
+    #[test]
+    fn indent_sequence_follows_the_format_setting() {
+        let indented = "items:\n  - a\n  - b\n";
+        let flush = "items:\n- a\n- b\n";
+
+        for indent_sequence in [true, false] {
+            let mut config = Config::default();
+            config.format.indent_sequence = indent_sequence;
+
+            let results = lint(config.clone(), indented, "indent-sequence");
+            assert_eq!(results.len(), usize::from(!indent_sequence), "indented, setting {}", indent_sequence);
+
+            let results = lint(config, flush, "indent-sequence");
+            assert_eq!(results.len(), usize::from(indent_sequence), "flush, setting {}", indent_sequence);
+        }
+    }
+
+    #[test]
+    fn final_line_flags_a_trailing_comment() {
+        let mut config = Config::default();
+        config.rules.final_line = config::SeverityRule::warning();
+
+        let results = lint(config.clone(), "key: value\n# end of file\n", "final-line");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (2, 1));
+
+        assert!(lint(config.clone(), "# header\nkey: value\n", "final-line").is_empty());
+        // Пустые строки в конце — забота new-line-at-end-of-file
+        assert!(lint(config, "key: value\n\n\n\n", "final-line").is_empty());
+    }
+
+    #[test]
+    fn block_scalar_style_flags_the_minority_style() {
+        let content = "a: |\n  one\nb: >\n  two\nc: |-\n  three\n";
+
+        let results = lint(Config::default(), content, "block-scalar-style");
+        assert_eq!(results.iter().map(|r| r.line).collect::<Vec<_>>(), vec![3]);
+
+        let mut config = Config::default();
+        config.rules.block_scalar_style.prefer = BlockScalarStyle::Folded;
+        let results = lint(config, content, "block-scalar-style");
+        assert_eq!(results.iter().map(|r| r.line).collect::<Vec<_>>(), vec![1, 5]);
+    }
+
+    #[test]
+    fn environment_markers_checks_matching_files_only() {
+        let mut config = Config::default();
+        let markers = HashMap::from([("environment".to_string(), "production".to_string())]);
+        config.rules.environment_markers.files.insert("*.prod.yaml".to_string(), markers);
+        let content = "environment: staging\n";
+
+        let results = lint_named(config.clone(), "app.prod.yaml", content, "environment-markers");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (1, 14));
+        assert!(results[0].message.contains("found 'staging'"), "{}", results[0].message);
+
+        assert!(lint_named(config.clone(), "app.prod.yaml", "environment: production\n", "environment-markers").is_empty());
+        assert!(lint_named(config, "app.dev.yaml", content, "environment-markers").is_empty());
+    }
+
+    #[test]
+    fn stray_comma_flags_block_items_only() {
+        let content = "items:\n  - first,\n  - second\nflow: [a, b]\n";
+
+        let results = lint(Config::default(), content, "stray-comma");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (2, 10));
+    }
+
+    #[test]
+    fn no_inline_secrets_allows_references() {
+        let results = lint(Config::default(), "db:\n  password: hunter2\n", "no-inline-secrets");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (2, 3));
+
+        assert!(lint(Config::default(), "db:\n  password: ${DB_PASS}\n", "no-inline-secrets").is_empty());
+    }
+
+    #[test]
+    fn line_length_counts_characters() {
+        let mut config = Config::default();
+        config.rules.line_length.max = 10;
+
+        // 13 символов, но 22 байта
+        let results = lint(config.clone(), "k: привет мир\n", "line-length");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].column, results[0].length), (11, 3));
+        assert_eq!(results[0].message, "Line too long (13 > 10)");
+
+        assert!(lint(config, "k: привет\n", "line-length").is_empty());
+    }
+
+    #[test]
+    fn homogeneous_values_flags_mixed_mappings() {
+        let mut config = Config::default();
+        config.rules.homogeneous_values.level = Severity::Warning;
+        let content = "app:\n  name: web\n  port: 80\n  env:\n    A: 1\n  volumes:\n    - data\n";
+
+        let results = lint(config.clone(), content, "homogeneous-values");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line, 1);
+        assert!(results[0].message.contains("'app' mixes 2 scalar and 2 collection"), "{}", results[0].message);
+
+        assert!(lint(config, "app:\n  name: web\n  port: 80\n  replicas: 2\n", "homogeneous-values").is_empty());
+    }
+
+    #[test]
+    fn comments_indentation_flags_over_indented_comment() {
+        let content = "app:\n  name: web\n      # stray comment\n  port: 80\n  # aligned comment\n  replicas: 2\n";
+
+        let results = lint(Config::default(), content, "comments-indentation");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (3, 7));
+    }
+
+    #[test]
+    fn duplicate_blocks_flags_repeated_collections() {
+        let mut config = Config::default();
+        config.rules.duplicate_blocks.level = Severity::Warning;
+        let block = "    resources:\n      cpu: 100m\n      memory: 128Mi\n";
+        let content = format!("web:\n{}worker:\n{}", block, block);
+
+        // Сообщается внешний блок, а не повтор `resources` внутри него
+        let results = lint(config.clone(), &content, "duplicate-blocks");
+        assert_eq!(results.iter().map(|r| r.line).collect::<Vec<_>>(), vec![1, 5]);
+        assert!(results[0].message.starts_with("Block 'web' is repeated 2 times (lines 1, 5)"), "{}", results[0].message);
+
+        let different = format!("web:\n{}worker:\n{}", block, block.replace("128Mi", "256Mi"));
+        assert!(lint(config, &different, "duplicate-blocks").is_empty());
+    }
+
+    #[test]
+    fn key_occurrence_limit_reports_the_first_extra_key() {
+        let mut config = Config::default();
+        config.rules.key_occurrence_limit.limits.insert("spec.**.image".to_string(), 2);
+        let content = "spec:\n  containers:\n    - image: a\n    - image: b\n  sidecars:\n    - image: c\n";
+
+        let results = lint(config.clone(), content, "key-occurrence-limit");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (6, 7));
+        assert_eq!(results[0].message, "Key 'spec.**.image' occurs 3 times (limit 2) at lines 3, 4, 6");
+
+        let within = content.replace("  sidecars:\n    - image: c\n", "");
+        assert!(lint(config, &within, "key-occurrence-limit").is_empty());
+    }
+
+    #[test]
+    fn leading_zeros_requires_quotes() {
+        let results = lint(Config::default(), "zip: 01234\n", "leading-zeros");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].column, results[0].length), (6, 5));
+
+        // Совет правила не должен противоречить остальным правилам
+        let checker = RuleChecker::new(Config::default()).unwrap();
+        let results = checker.check_file("---\nzip: \"01234\"\nzero: 0\nratio: 0.5\n", "test.yaml");
+        assert!(results.is_empty(), "{:?}", results);
+        assert_eq!(checker.check_file("---\ncount: \"42\"\n", "test.yaml")[0].rule, "value-types");
+    }
+
+    #[test]
+    fn yaml_version_selects_truthy_values() {
+        let content = "enabled: on\n";
+        assert_eq!(lint(Config::default(), content, "truthy").len(), 1);
+
+        let config = Config { yaml_version: YamlVersion::V1_2, ..Config::default() };
+        assert!(lint(config, content, "truthy").is_empty());
+
+        // Директива в файле важнее настройки
+        assert!(lint(Config::default(), "%YAML 1.2\n---\nenabled: on\n", "truthy").is_empty());
+    }
+
+    #[test]
+    fn block_scalar_chomping_requires_an_indicator() {
+        let mut config = Config::default();
+        config.rules.block_scalar_chomping.level = Severity::Warning;
+
+        let results = lint(config.clone(), "foo: |\n  text\n", "block-scalar-chomping");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (1, 6));
+
+        assert!(lint(config, "foo: |-\n  text\nbar: >+\n  text\n", "block-scalar-chomping").is_empty());
+    }
+
+    #[test]
+    fn float_precision_flags_extra_decimal_places() {
+        let mut config = Config::default();
+        config.rules.float_precision.max_decimal_places = 4;
+
+        let results = lint(config.clone(), "ratio: 0.333333333\n", "float-precision");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].column, results[0].length), (8, 11));
+
+        assert!(lint(config, "ratio: 0.3333\nversion: \"1.123456\"\n", "float-precision").is_empty());
+    }
+
+    #[test]
+    fn required_fields_selected_by_content() {
+        let mut config = Config::default();
+        config.rules.required_fields.content.push(config::ContentRequirement {
+            when: config::ContentSelector { path: "/kind".to_string(), equals: "Deployment".to_string() },
+            fields: vec!["spec.replicas".to_string()],
+        });
+
+        let results = lint(config.clone(), "kind: Deployment\nspec:\n  template: {}\n", "required-fields");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message, "Missing required field: replicas");
+        assert_eq!(results[0].line, 2);
+
+        assert!(lint(config, "kind: Service\nspec:\n  ports: []\n", "required-fields").is_empty());
+    }
+
+    #[test]
+    fn strict_indent_flags_misaligned_lines() {
+        let mut config = Config::default();
+        config.rules.strict_indent.level = Severity::Warning;
+        let content = "app:\n  name: web\n  env:\n      A: 1\n  port: 80\n";
+
+        let results = lint(config.clone(), content, "strict-indent");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (4, 7));
+
+        assert!(lint(config, &content.replace("      A", "    A"), "strict-indent").is_empty());
+    }
+
+    #[test]
+    fn compare_to_defaults_reports_changed_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let defaults = dir.path().join("defaults.yaml");
+        fs::write(&defaults, "replicas: 1\nimage:\n  tag: stable\n  pull: IfNotPresent\n").unwrap();
+
+        let mut config = Config::default();
+        config.rules.compare_to_defaults.reference = Some(defaults.to_string_lossy().to_string());
+        config.rules.compare_to_defaults.level = Severity::Warning;
+        let content = "replicas: 1\nimage:\n  tag: latest\n  pull: IfNotPresent\n";
+
+        let results = lint(config, content, "compare-to-defaults");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (3, 8));
+        assert!(results[0].message.starts_with("Value of 'image.tag' differs from defaults"), "{}", results[0].message);
+    }
+
+    #[test]
+    fn rule_scopes_limit_where_a_rule_applies() {
+        let mut config = Config::default();
+        config.rules.required_fields.paths = HashMap::from([("*.yaml".to_string(), vec!["kind".to_string()])]);
+        config.rule_scopes.insert("required-fields".to_string(), vec!["k8s/**".to_string()]);
+        let content = "name: web\n";
+
+        assert_eq!(lint_named(config.clone(), "k8s/web.yaml", content, "required-fields").len(), 1);
+        assert!(lint_named(config.clone(), "docs/web.yaml", content, "required-fields").is_empty());
+        assert!(lint_named(config, "./docs/web.yaml", content, "required-fields").is_empty());
+    }
+
+    #[test]
+    fn duplicate_named_items_flags_repeated_env_names() {
+        let content = concat!(
+            "containers:\n  - name: app\n    env:\n",
+            "      - name: MODE\n        value: a\n",
+            "      - name: PORT\n        value: '80'\n",
+            "      - name: MODE\n        value: b\n",
+        );
+
+        let results = lint(Config::default(), content, "duplicate-named-items");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (8, 15));
+        assert!(results[0].message.contains("MODE"), "{}", results[0].message);
+
+        let unique = content.replace("name: MODE\n        value: b", "name: HOST\n        value: b");
+        assert!(lint(Config::default(), &unique, "duplicate-named-items").is_empty());
+    }
+
+    #[test]
+    fn invalid_file_still_reports_line_findings() {
+        let results = RuleChecker::new(Config::default()).unwrap().check_file("key: [a, b   \nother: 1  \n", "test.yaml");
+
+        assert!(results.iter().any(|r| r.rule == "syntax"), "{:?}", results);
+        let trailing: Vec<usize> = results.iter().filter(|r| r.rule == "trailing-spaces").map(|r| r.line).collect();
+        assert_eq!(trailing, vec![1, 2]);
+    }
+
+    #[test]
+    fn mixed_spaces_and_tabs_are_reported_where_they_mix() {
+        let mut config = Config::default();
+        config.rules.indentation.forbid_tabs = false;
+
+        for (content, column) in [("map:\n  \tkey: 1\n", 3), ("map:\n\t  key: 1\n", 2)] {
+            let mixed: Vec<_> = lint(config.clone(), content, "indentation")
+                .into_iter()
+                .filter(|r| r.message == "Mixed spaces and tabs in indentation")
+                .collect();
+            assert_eq!(mixed.len(), 1, "{:?}", content);
+            assert_eq!((mixed[0].line, mixed[0].column), (2, column), "{:?}", content);
+        }
+
+        assert!(lint(config, "map:\n\tkey: 1\n", "indentation").iter().all(|r| !r.message.contains("Mixed")));
+    }
+
+    #[test]
+    fn key_order_template_reports_the_out_of_place_key() {
+        let mut config = Config::default();
+        config.rules.key_order_template.level = Severity::Warning;
+
+        let results = lint(config.clone(), "kind: Service\napiVersion: v1\nmetadata: {}\n", "key-order-template");
+        assert_eq!(results.len(), 1);
+        assert_eq!((results[0].line, results[0].column), (2, 1));
+
+        let ordered = "apiVersion: v1\ncustom: true\nkind: Service\nmetadata: {}\n";
+        assert!(lint(config, ordered, "key-order-template").is_empty());
+    }
+
+    #[test]
+    fn control_chars_flags_invisible_characters() {
+        let results = lint(Config::default(), "a: \"x\0y\"\nb: tab\there\nc: \u{b}value\r\n", "control-chars");
+
+        let found: Vec<_> = results.iter().map(|r| (r.line, r.column, r.message.as_str())).collect();
+        assert_eq!(found, vec![
+            (1, 6, "Control character U+0000 in content"),
+            (3, 4, "Control character U+000B in content"),
+        ]);
+    }
+
+    #[test]
+    fn octal_values_require_quotes() {
+        let mut config = Config::default();
+        config.rules.octal_values.level = Severity::Warning;
+
+        let results = lint(config.clone(), "mode: 0755\nexplicit: 0o755\n", "octal-values");
+        assert_eq!(results.iter().map(|r| (r.line, r.column, r.length)).collect::<Vec<_>>(),
+                   vec![(1, 7, 4), (2, 11, 5)]);
+
+        assert!(lint(config.clone(), "mode: \"0755\"\nzero: 0\n", "octal-values").is_empty());
+
+        config.rules.octal_values.forbid_explicit = false;
+        assert_eq!(lint(config.clone(), "mode: 0755\nexplicit: 0o755\n", "octal-values").len(), 1);
+
+        // Со всеми правилами: одна находка на `0755`, а совет правила проходит чисто
+        let checker = RuleChecker::new(config).unwrap();
+        let mut found: Vec<(usize, String)> = checker.check_file("---\nmode: 0755\nzip: 09876\n", "test.yaml")
+            .into_iter()
+            .map(|r| (r.line, r.rule))
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![(2, "octal-values".to_string()), (3, "leading-zeros".to_string())]);
+        let results = checker.check_file("---\nmode: \"0755\"\n", "test.yaml");
+        assert!(results.is_empty(), "{:?}", results);
+    }
+
+    #[test]
+    fn duplicate_named_items_flags_nested_names_in_a_manifest() {
+        let content = concat!(
+            "apiVersion: apps/v1\n",
+            "kind: Deployment\n",
+            "metadata:\n  name: web\n",
+            "spec:\n  template:\n    spec:\n      containers:\n",
+            "        - name: app\n          env:\n            - name: MODE\n",
+            "        - name: sidecar\n          env:\n            - name: MODE\n",
+            "            - name: MODE\n",
+            "        - name: app\n          image: web:2\n",
+        );
+
+        let results = lint(Config::default(), content, "duplicate-named-items");
+        let found: Vec<(usize, usize, &str)> =
+            results.iter().map(|r| (r.line, r.column, r.message.as_str())).collect();
+
+        assert_eq!(found.len(), 2, "{:#?}", found);
+        assert_eq!((found[0].0, found[0].1), (15, 21));
+        assert!(found[0].2.contains("'MODE' in 'spec.template.spec.containers[1].env'"), "{}", found[0].2);
+        assert_eq!((found[1].0, found[1].1), (16, 17));
+        assert!(found[1].2.contains("'app' in 'spec.template.spec.containers' (first defined at line 9)"), "{}", found[1].2);
+    }
 }
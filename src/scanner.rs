@@ -0,0 +1,504 @@
+//! Построчный лексер YAML.
+//!
+//! `serde_yaml::Value` теряет всё, что не относится к данным: позиции,
+//! стиль кавычек, комментарии, якоря. Правилам, которым важен исходный
+//! текст токена, нужен этот лексер. Он не претендует на полноту спецификации:
+//! каждая строка разбирается отдельно, а между строками переносится только
+//! состояние блочных скаляров, многострочных кавычек и flow-коллекций.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScalarStyle {
+    Plain,
+    SingleQuoted,
+    DoubleQuoted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    /// Маркер `---`
+    DocumentStart,
+    /// Маркер `...`
+    DocumentEnd,
+    /// Индикатор элемента последовательности `-`
+    SequenceEntry,
+    /// Скаляр, за которым следует `:`
+    Key(ScalarStyle),
+    /// Скаляр в позиции значения или элемента
+    Scalar(ScalarStyle),
+    Colon,
+    /// `[` или `{`
+    FlowStart,
+    /// `]` или `}`
+    FlowEnd,
+    Comma,
+    Anchor,
+    Alias,
+    Tag,
+    /// Заголовок блочного скаляра: `|`, `>-`, `|+2` и т.п.
+    BlockScalar,
+    Comment,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Байтовое смещение начала токена в строке
+    pub offset: usize,
+    /// Номер символа (не байта) начала токена, начиная с 1
+    pub column: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Line<'a> {
+    /// Номер строки, начиная с 1
+    pub number: usize,
+    pub text: &'a str,
+    /// Токены строки; пусто для содержимого блочных скаляров
+    pub tokens: Vec<Token<'a>>,
+}
+
+impl<'a> Token<'a> {
+    /// Текст скаляра без обрамляющих кавычек (экранирование не раскрывается)
+    pub fn unquoted(&self) -> &'a str {
+        let text = self.text;
+        match self.kind {
+            TokenKind::Key(ScalarStyle::Plain) | TokenKind::Scalar(ScalarStyle::Plain) => text,
+            TokenKind::Key(_) | TokenKind::Scalar(_) if text.len() >= 2 => &text[1..text.len() - 1],
+            _ => text,
+        }
+    }
+}
+
+impl<'a> Line<'a> {
+    /// Ключи строки вместе со скалярным значением, если оно на той же строке
+    pub fn key_values(&self) -> Vec<(&Token<'a>, Option<&Token<'a>>)> {
+        let mut pairs = vec![];
+
+        for (i, token) in self.tokens.iter().enumerate() {
+            if !matches!(token.kind, TokenKind::Key(_)) {
+                continue;
+            }
+
+            let value = self.tokens[i + 1..]
+                .iter()
+                .find(|t| !matches!(t.kind, TokenKind::Colon | TokenKind::Anchor | TokenKind::Tag))
+                .filter(|t| matches!(t.kind, TokenKind::Scalar(_)));
+
+            pairs.push((token, value));
+        }
+
+        pairs
+    }
+
+    /// Пробелы вокруг двоеточий после ключей: байтовые диапазоны до `:`
+    /// и после него. Пробелы перед комментарием и в конце строки не учитываются
+    pub fn colon_gaps(&self) -> Vec<ColonGap> {
+        let mut gaps = vec![];
+
+        for (i, colon) in self.tokens.iter().enumerate() {
+            if colon.kind != TokenKind::Colon || i == 0 || !matches!(self.tokens[i - 1].kind, TokenKind::Key(_)) {
+                continue;
+            }
+
+            let key = &self.tokens[i - 1];
+            let after = self.tokens
+                .get(i + 1)
+                .filter(|next| next.kind != TokenKind::Comment)
+                .map(|next| colon.offset + 1..next.offset);
+
+            gaps.push(ColonGap { before: key.offset + key.text.len()..colon.offset, after });
+        }
+
+        gaps
+    }
+
+    /// Пробелы после индикаторов `-`, за которыми на строке есть содержимое
+    pub fn hyphen_gaps(&self) -> Vec<HyphenGap<'_, 'a>> {
+        self.tokens
+            .windows(2)
+            .filter(|pair| pair[0].kind == TokenKind::SequenceEntry && pair[1].kind != TokenKind::Comment)
+            .map(|pair| HyphenGap {
+                dash: &pair[0],
+                after: pair[0].offset + 1..pair[1].offset,
+                starts_block: matches!(pair[1].kind, TokenKind::Key(_) | TokenKind::SequenceEntry),
+            })
+            .collect()
+    }
+
+    /// Пробелы вокруг запятых flow-коллекций; соседний токен должен быть
+    /// на той же строке, комментарий соседом не считается
+    pub fn comma_gaps(&self) -> Vec<CommaGap<'_, 'a>> {
+        let mut gaps = vec![];
+
+        for (i, comma) in self.tokens.iter().enumerate() {
+            if comma.kind != TokenKind::Comma {
+                continue;
+            }
+
+            let before = i
+                .checked_sub(1)
+                .map(|p| &self.tokens[p])
+                .map(|prev| prev.offset + prev.text.len()..comma.offset);
+            let after = self.tokens
+                .get(i + 1)
+                .filter(|next| next.kind != TokenKind::Comment)
+                .map(|next| comma.offset + 1..next.offset);
+
+            gaps.push(CommaGap { comma, before, after });
+        }
+
+        gaps
+    }
+}
+
+pub struct CommaGap<'l, 'a> {
+    pub comma: &'l Token<'a>,
+    pub before: Option<Range<usize>>,
+    pub after: Option<Range<usize>>,
+}
+
+pub struct ColonGap {
+    pub before: Range<usize>,
+    /// `None`, если после двоеточия на строке нет значения
+    pub after: Option<Range<usize>>,
+}
+
+/// Пробелы после индикатора `-` до содержимого элемента на той же строке
+pub struct HyphenGap<'l, 'a> {
+    pub dash: &'l Token<'a>,
+    pub after: Range<usize>,
+    /// Элемент начинается с ключа или вложенного `-`: по их колонке
+    /// выравниваются следующие строки элемента
+    pub starts_block: bool,
+}
+
+/// Начало блочной последовательности, являющейся значением ключа
+pub struct KeySequence {
+    /// Индекс строки с первым элементом (с 0)
+    pub line_index: usize,
+    /// Байтовая колонка ключа (с 0)
+    pub key_offset: usize,
+    /// Байтовая колонка первого `-` (с 0)
+    pub dash_offset: usize,
+}
+
+/// Находит последовательности вида `key:` с элементами `- ...` на следующих строках
+pub fn key_sequences(lines: &[Line]) -> Vec<KeySequence> {
+    let mut sequences = vec![];
+    let mut pending_key: Option<usize> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let mut tokens = line.tokens.iter().filter(|t| t.kind != TokenKind::Comment);
+
+        let Some(first) = tokens.clone().next() else {
+            continue;
+        };
+
+        if let Some(key_offset) = pending_key.take() {
+            if first.kind == TokenKind::SequenceEntry {
+                sequences.push(KeySequence { line_index: index, key_offset, dash_offset: first.offset });
+            }
+        }
+
+        // Строка заканчивается на `key:` — значение начнётся на следующих строках
+        let significant: Vec<_> = tokens
+            .by_ref()
+            .filter(|t| !matches!(t.kind, TokenKind::Anchor | TokenKind::Tag))
+            .collect();
+        if let [.., key, colon] = significant.as_slice() {
+            if matches!(key.kind, TokenKind::Key(_)) && colon.kind == TokenKind::Colon {
+                pending_key = Some(key.offset);
+            }
+        }
+    }
+
+    sequences
+}
+
+/// Индекс первой строки с содержимым: комментарии, пустые строки
+/// и директивы (`%YAML`, `%TAG`) пропускаются
+pub fn first_content_line(lines: &[Line]) -> Option<usize> {
+    lines.iter().position(|line| {
+        !line.text.starts_with('%') && line.tokens.iter().any(|t| t.kind != TokenKind::Comment)
+    })
+}
+
+/// Версия из директивы `%YAML` перед первым документом
+pub fn yaml_directive<'a>(lines: &[Line<'a>]) -> Option<&'a str> {
+    lines
+        .iter()
+        .take_while(|line| line.tokens.first().is_none_or(|t| t.kind != TokenKind::DocumentStart))
+        .find_map(|line| line.text.strip_prefix("%YAML"))
+        .and_then(|rest| rest.split_whitespace().next())
+}
+
+/// Скаляры, помеченные одним из тегов: `config: !include other.yaml`
+pub fn tagged_scalars<'l, 'a>(lines: &'l [Line<'a>], tags: &[String]) -> Vec<(&'l Line<'a>, &'l Token<'a>)> {
+    let mut found = vec![];
+
+    for line in lines {
+        for pair in line.tokens.windows(2) {
+            if pair[0].kind == TokenKind::Tag
+                && tags.iter().any(|t| t == pair[0].text)
+                && matches!(pair[1].kind, TokenKind::Scalar(_))
+            {
+                found.push((line, &pair[1]));
+            }
+        }
+    }
+
+    found
+}
+
+struct State {
+    flow_depth: usize,
+    /// Отступ родительского узла открытого блочного скаляра
+    block_parent: Option<usize>,
+    /// Символ кавычки незакрытого многострочного скаляра
+    open_quote: Option<char>,
+}
+
+pub fn scan(content: &str) -> Vec<Line<'_>> {
+    let mut state = State {
+        flow_depth: 0,
+        block_parent: None,
+        open_quote: None,
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, text)| scan_line(i + 1, text, &mut state))
+        .collect()
+}
+
+fn scan_line<'a>(number: usize, text: &'a str, state: &mut State) -> Line<'a> {
+    let indent = text.len() - text.trim_start_matches(' ').len();
+
+    if let Some(parent) = state.block_parent {
+        if text.trim().is_empty() || indent > parent {
+            return Line { number, text, tokens: vec![] };
+        }
+        state.block_parent = None;
+    }
+
+    let bytes = text.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    let push = |tokens: &mut Vec<Token<'a>>, kind, start: usize, end: usize| {
+        tokens.push(Token {
+            kind,
+            text: &text[start..end],
+            offset: start,
+            column: text[..start].chars().count() + 1,
+        });
+    };
+
+    // Продолжение многострочного скаляра в кавычках
+    if let Some(quote) = state.open_quote {
+        let style = if quote == '"' { ScalarStyle::DoubleQuoted } else { ScalarStyle::SingleQuoted };
+        match find_closing_quote(text, 0, quote) {
+            Some(end) => {
+                state.open_quote = None;
+                push(&mut tokens, TokenKind::Scalar(style), 0, end);
+                i = end;
+            }
+            None => {
+                push(&mut tokens, TokenKind::Scalar(style), 0, text.len());
+                return Line { number, text, tokens };
+            }
+        }
+    }
+
+    let at_space = |pos: usize| pos >= bytes.len() || bytes[pos] == b' ' || bytes[pos] == b'\t';
+
+    while i < bytes.len() {
+        let c = bytes[i];
+
+        if c == b' ' || c == b'\t' {
+            i += 1;
+            continue;
+        }
+
+        if c == b'#' && (i == 0 || bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') {
+            push(&mut tokens, TokenKind::Comment, i, text.len());
+            break;
+        }
+
+        let in_flow = state.flow_depth > 0;
+
+        if i == 0 && !in_flow && (text.starts_with("---") || text.starts_with("...")) && at_space(3) {
+            let kind = if c == b'-' { TokenKind::DocumentStart } else { TokenKind::DocumentEnd };
+            push(&mut tokens, kind, 0, 3);
+            i = 3;
+            continue;
+        }
+
+        match c {
+            b'-' if !in_flow && at_space(i + 1) => {
+                push(&mut tokens, TokenKind::SequenceEntry, i, i + 1);
+                i += 1;
+            }
+            b'[' | b'{' => {
+                state.flow_depth += 1;
+                push(&mut tokens, TokenKind::FlowStart, i, i + 1);
+                i += 1;
+            }
+            b']' | b'}' => {
+                state.flow_depth = state.flow_depth.saturating_sub(1);
+                push(&mut tokens, TokenKind::FlowEnd, i, i + 1);
+                i += 1;
+            }
+            b',' if in_flow => {
+                push(&mut tokens, TokenKind::Comma, i, i + 1);
+                i += 1;
+            }
+            b':' if at_space(i + 1) || (in_flow && is_flow_indicator(bytes.get(i + 1))) => {
+                push(&mut tokens, TokenKind::Colon, i, i + 1);
+                i += 1;
+            }
+            b'&' | b'*' | b'!' => {
+                let end = scan_until_space(bytes, i, in_flow);
+                let kind = match c {
+                    b'&' => TokenKind::Anchor,
+                    b'*' => TokenKind::Alias,
+                    _ => TokenKind::Tag,
+                };
+                push(&mut tokens, kind, i, end);
+                i = end;
+            }
+            b'|' | b'>' if !in_flow => {
+                let end = scan_until_space(bytes, i, false);
+                push(&mut tokens, TokenKind::BlockScalar, i, end);
+                state.block_parent = Some(block_parent_indent(&tokens, indent));
+                i = end;
+            }
+            b'"' | b'\'' => {
+                let quote = c as char;
+                let style = if quote == '"' { ScalarStyle::DoubleQuoted } else { ScalarStyle::SingleQuoted };
+                let end = match find_closing_quote(text, i + 1, quote) {
+                    Some(end) => end,
+                    None => {
+                        state.open_quote = Some(quote);
+                        text.len()
+                    }
+                };
+                let kind = scalar_kind(bytes, end, in_flow, style);
+                push(&mut tokens, kind, i, end);
+                i = end;
+            }
+            _ => {
+                let end = scan_plain(bytes, i, in_flow);
+                let kind = scalar_kind(bytes, end, in_flow, ScalarStyle::Plain);
+                push(&mut tokens, kind, i, end);
+                i = end.max(i + 1);
+            }
+        }
+    }
+
+    Line { number, text, tokens }
+}
+
+fn is_flow_indicator(c: Option<&u8>) -> bool {
+    matches!(c, Some(b',') | Some(b']') | Some(b'}') | Some(b'[') | Some(b'{'))
+}
+
+fn scan_until_space(bytes: &[u8], start: usize, in_flow: bool) -> usize {
+    let mut end = start;
+    while end < bytes.len()
+        && bytes[end] != b' '
+        && bytes[end] != b'\t'
+        && !(in_flow && is_flow_indicator(Some(&bytes[end])))
+    {
+        end += 1;
+    }
+    end
+}
+
+/// Возвращает позицию сразу после закрывающей кавычки
+fn find_closing_quote(text: &str, start: usize, quote: char) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let q = quote as u8;
+    let mut i = start;
+
+    while i < bytes.len() {
+        if quote == '"' && bytes[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if bytes[i] == q {
+            // В одинарных кавычках '' — экранированная кавычка
+            if quote == '\'' && bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return Some(i + 1);
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Конец простого (plain) скаляра; завершающие пробелы не включаются
+fn scan_plain(bytes: &[u8], start: usize, in_flow: bool) -> usize {
+    let mut i = start;
+    let mut end = start;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let next_is_space = i + 1 >= bytes.len() || bytes[i + 1] == b' ' || bytes[i + 1] == b'\t';
+
+        if c == b':' && (next_is_space || (in_flow && is_flow_indicator(bytes.get(i + 1)))) {
+            break;
+        }
+        if c == b'#' && i > start && (bytes[i - 1] == b' ' || bytes[i - 1] == b'\t') {
+            break;
+        }
+        if in_flow && is_flow_indicator(Some(&c)) {
+            break;
+        }
+        if c != b' ' && c != b'\t' {
+            end = i + 1;
+        }
+        i += 1;
+    }
+
+    end
+}
+
+/// Скаляр считается ключом, если за ним (через пробелы) следует `:`
+fn scalar_kind(bytes: &[u8], end: usize, in_flow: bool, style: ScalarStyle) -> TokenKind {
+    let mut i = end;
+    while i < bytes.len() && (bytes[i] == b' ' || bytes[i] == b'\t') {
+        i += 1;
+    }
+
+    let followed_by_colon = i < bytes.len()
+        && bytes[i] == b':'
+        && (i + 1 >= bytes.len()
+            || bytes[i + 1] == b' '
+            || bytes[i + 1] == b'\t'
+            || in_flow
+            || style != ScalarStyle::Plain);
+
+    if followed_by_colon {
+        TokenKind::Key(style)
+    } else {
+        TokenKind::Scalar(style)
+    }
+}
+
+/// Отступ узла, которому принадлежит блочный скаляр: колонка ключа или
+/// индикатора `-` на той же строке, иначе отступ строки
+fn block_parent_indent(tokens: &[Token], indent: usize) -> usize {
+    tokens
+        .iter()
+        .rev()
+        .find(|t| matches!(t.kind, TokenKind::Key(_) | TokenKind::SequenceEntry))
+        .map_or(indent, |t| t.offset)
+}
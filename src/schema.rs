@@ -0,0 +1,90 @@
+//! Проверка YAML-документов по JSON Schema.
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use serde_yaml::Value;
+use std::fs;
+use std::path::Path;
+
+/// Загружает схему; файл может быть в JSON или в YAML
+pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<JsonValue> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read schema {}", path.display()))?;
+    let schema: Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("failed to parse schema {}", path.display()))?;
+
+    serde_json::to_value(schema).with_context(|| format!("schema {} is not valid JSON", path.display()))
+}
+
+/// Проверяет каждый документ потока и возвращает ошибки вида
+/// `/spec/replicas: "x" is not of type "integer"`. Ошибка разбора
+/// документа возвращается как единственная ошибка валидации
+pub fn validate(schema: &JsonValue, content: &str) -> anyhow::Result<Vec<String>> {
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| anyhow::anyhow!("invalid schema: {}", e))?;
+
+    let mut documents = vec![];
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let value = match Value::deserialize(document) {
+            Ok(value) => value,
+            Err(e) => return Ok(vec![format!("Syntax error: {}", e)]),
+        };
+        documents.push(serde_json::to_value(value).context("document cannot be represented as JSON")?);
+    }
+
+    let mut errors = vec![];
+    for (number, instance) in documents.iter().enumerate() {
+        for error in validator.iter_errors(instance) {
+            let path = error.instance_path().to_string();
+            let path = if path.is_empty() { "/".to_string() } else { path };
+
+            if documents.len() > 1 {
+                errors.push(format!("document {}: {}: {}", number + 1, path, error));
+            } else {
+                errors.push(format!("{}: {}", path, error));
+            }
+        }
+    }
+
+    Ok(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn versioned_schema() -> JsonValue {
+        json!({
+            "type": "object",
+            "required": ["version"],
+            "properties": { "version": { "type": "integer" } },
+        })
+    }
+
+    #[test]
+    fn integer_version_passes() {
+        let errors = validate(&versioned_schema(), "---\nversion: 2\nname: app\n").unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn wrong_or_missing_version_fails() {
+        let errors = validate(&versioned_schema(), "---\nversion: \"2\"\n").unwrap();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("/version: "), "{:?}", errors);
+
+        let errors = validate(&versioned_schema(), "---\nname: app\n").unwrap();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("/: ") && errors[0].contains("version"), "{:?}", errors);
+    }
+
+    #[test]
+    fn errors_name_the_document_in_a_stream() {
+        let errors = validate(&versioned_schema(), "---\nversion: 1\n---\nversion: one\n").unwrap();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("document 2: /version: "), "{:?}", errors);
+    }
+}
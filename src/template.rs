@@ -0,0 +1,123 @@
+//! Проверка шаблонов, которые становятся YAML только после рендеринга.
+//!
+//! Строки, состоящие только из директив, становятся пустыми, а директивы
+//! внутри содержимого заменяются заглушками той же длины. Число строк не
+//! меняется, поэтому строки и колонки находок совпадают с исходным файлом.
+
+use clap::ValueEnum;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+lazy_static! {
+    // Строка, состоящая только из директив: `{{- if .Values.enabled }}`, `{{- end }}`
+    static ref DIRECTIVE_LINE_RE: Regex = Regex::new(r"^\s*(\{\{.*?\}\}\s*)+$").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateMode {
+    /// Шаблоны Helm (Go templates) с директивами `{{ ... }}`
+    Helm,
+}
+
+/// Правила, которые на заглушках дают ложные срабатывания: структура и
+/// значения появляются только после рендеринга
+pub const HELM_SKIPPED_RULES: &[&str] = &[
+    "empty-lines",
+    "required-fields",
+    "value-types",
+    "key-ordering",
+    "key-order-template",
+    "duplicate-blocks",
+    "duplicate-named-items",
+    "homogeneous-values",
+    "compare-to-defaults",
+];
+
+/// Заменяет директивы заглушками. Строки только из директив становятся
+/// пустыми, остальные директивы — скалярами `_1__…` той же длины
+pub fn mask_helm(content: &str) -> String {
+    let mut masked = String::with_capacity(content.len());
+    let mut counter = 0;
+
+    for line in content.split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+
+        if DIRECTIVE_LINE_RE.is_match(text) {
+            masked.push_str(&line[text.len()..]);
+        } else {
+            masked.push_str(line);
+        }
+    }
+
+    // Директивы внутри строк, в том числе занимающие несколько строк
+    let mut result = String::with_capacity(masked.len());
+    let mut rest = masked.as_str();
+
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let end = rest[start..].find("}}").map_or(rest.len(), |e| start + e + 2);
+
+        counter += 1;
+        result.push_str(&placeholder(&rest[start..end], counter));
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Заглушка той же длины в символах; переводы строк сохраняются.
+/// Номер делает заглушки разными, чтобы не было ложных повторов ключей
+fn placeholder(span: &str, number: usize) -> String {
+    let id = format!("_{}", number);
+    let mut id = id.chars();
+
+    span.chars()
+        .map(|c| match c {
+            '\n' | '\r' => c,
+            _ => id.next().unwrap_or('_'),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::linter::YamlLinter;
+
+    const CHART: &str = "{{- if .Values.enabled }}\n\
+                         apiVersion: v1\n\
+                         kind: ConfigMap\n\
+                         metadata:\n  \
+                         name: {{ .Release.Name }}   \n\
+                         data:\n  \
+                         key: {{ .Values.key | quote }}\n\
+                         {{- end }}\n";
+
+    #[test]
+    fn masking_keeps_lines_and_columns() {
+        let masked = mask_helm(CHART);
+
+        assert_eq!(masked.lines().count(), CHART.lines().count());
+        assert_eq!(masked.lines().next(), Some(""));
+        for (original, masked) in CHART.lines().zip(masked.lines()).skip(1).take(6) {
+            assert_eq!(original.chars().count(), masked.chars().count(), "{:?}", masked);
+        }
+    }
+
+    #[test]
+    fn findings_keep_their_positions() {
+        let config = Config { template: Some(TemplateMode::Helm), ..Config::default() };
+        let linter = YamlLinter::new(config).unwrap();
+
+        let report = linter.lint_reader(CHART.as_bytes(), "chart.yaml").unwrap();
+        let trailing = report.results.iter().find(|r| r.rule == "trailing-spaces").expect("trailing spaces are found");
+
+        assert_eq!(trailing.line, 5);
+        assert_eq!(trailing.column, "  name: {{ .Release.Name }}".len() + 1);
+        assert_eq!(trailing.snippet, "  name: {{ .Release.Name }}   ");
+    }
+}
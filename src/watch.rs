@@ -0,0 +1,70 @@
+//! Режим `--watch`: после первой проверки следит за путём и заново
+//! проверяет только изменившиеся YAML-файлы.
+
+use crate::linter::YamlLinter;
+use anyhow::Context;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Редакторы сохраняют файл несколькими записями подряд, поэтому проверка
+/// начинается, когда события затихли на это время
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Работает до прерывания процесса
+pub fn run(linter: &YamlLinter, target: &str, verbose: bool) -> anyhow::Result<()> {
+    let root = Path::new(target);
+    // События приходят с абсолютными путями
+    let base = fs::canonicalize(root).with_context(|| format!("failed to resolve {}", root.display()))?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("failed to start file watcher")?;
+    watcher
+        .watch(root, RecursiveMode::Recursive)
+        .with_context(|| format!("failed to watch {}", root.display()))?;
+
+    println!("\nWatching {} for changes (Ctrl+C to stop)", root.display());
+
+    while let Ok(event) = rx.recv() {
+        let mut changed = BTreeSet::new();
+        collect(event, &mut changed);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect(event, &mut changed);
+        }
+
+        let mut reports = vec![];
+        for path in changed {
+            // Пути в отчётах — как при первой проверке, относительно цели
+            let path = match path.strip_prefix(&base) {
+                Ok(relative) if root.is_dir() => root.join(relative),
+                _ => root.to_path_buf(),
+            };
+
+            if !linter.should_lint(root, &path) {
+                continue;
+            }
+
+            match linter.lint_file_with_includes(&path) {
+                Ok(file_reports) => reports.extend(file_reports),
+                Err(e) => eprintln!("Error processing {}: {:#}", path.display(), e),
+            }
+        }
+
+        if !reports.is_empty() {
+            linter.print_results(&reports, verbose);
+        }
+    }
+
+    Ok(())
+}
+
+fn collect(event: notify::Result<Event>, changed: &mut BTreeSet<PathBuf>) {
+    match event {
+        Ok(event) if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) => changed.extend(event.paths),
+        Ok(_) => {}
+        Err(e) => eprintln!("Watch error: {}", e),
+    }
+}